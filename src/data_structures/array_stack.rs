@@ -0,0 +1,362 @@
+use std::fmt;
+use std::iter::{FromIterator, Rev};
+use std::slice;
+use std::vec;
+use data_structures::stack::{Push, Pop};
+
+/// Stack stored in a Vec, with its top at the end
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArrayStack<T> {
+    items: Vec<T>
+}
+
+impl<T> ArrayStack<T> {
+    pub fn new() -> ArrayStack<T> {
+        ArrayStack { items: Vec::new() }
+    }
+
+    /// Construct an empty stack with room for capacity elements before it reallocates
+    pub fn with_capacity(capacity:usize) -> ArrayStack<T> {
+        ArrayStack { items: Vec::with_capacity(capacity) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Make room for at least additional more elements
+    pub fn reserve(&mut self, additional:usize) {
+        self.items.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.items.shrink_to_fit();
+    }
+
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Return the element on top without removing it
+    pub fn peek(&self) -> Option<&T> {
+        self.items.last()
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.items.last_mut()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Remove every element, yielding them top to bottom as pop would
+    pub fn drain(&mut self) -> Rev<vec::Drain<'_, T>> {
+        self.items.drain(..).rev()
+    }
+
+    /// Return the elements bottom to top
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Iterate over the elements bottom to top
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.items.iter_mut()
+    }
+}
+
+impl<T> Push<T> for ArrayStack<T> where T : Clone {
+    fn push(&mut self, data:&T) {
+        self.items.push(data.clone());
+    }
+}
+
+impl<T> Pop<T> for ArrayStack<T> where T : Clone {
+    fn pop(&mut self) -> Option<T> {
+        self.items.pop()
+    }
+}
+
+impl<T> Default for ArrayStack<T> {
+    fn default() -> ArrayStack<T> {
+        ArrayStack::new()
+    }
+}
+
+impl<T> FromIterator<T> for ArrayStack<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter:I) -> ArrayStack<T> {
+        ArrayStack { items: iter.into_iter().collect() }
+    }
+}
+
+impl<T> Extend<T> for ArrayStack<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter:I) {
+        self.items.extend(iter);
+    }
+}
+
+impl<T> IntoIterator for ArrayStack<T> {
+    type Item=T;
+    type IntoIter=vec::IntoIter<T>;
+    fn into_iter(self) -> vec::IntoIter<T> {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArrayStack<T> {
+    type Item=&'a T;
+    type IntoIter=slice::Iter<'a, T>;
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ArrayStack<T> {
+    type Item=&'a mut T;
+    type IntoIter=slice::IterMut<'a, T>;
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// Stack that also returns its smallest and largest element in constant time
+///
+/// Next to each element it records the positions of the minimum and maximum of the elements up to it,
+/// which stay valid until that element is popped. There is no peek_mut, as changing an element in place
+/// would make those records stale.
+/// # Example
+///
+/// ```
+/// use mldsa_rust::data_structures::array_stack::MinMaxStack;
+/// use mldsa_rust::data_structures::stack::{Push, Pop};
+/// let mut stack:MinMaxStack<i32>=vec![3, 1, 4].into_iter().collect();
+/// assert_eq!((stack.min(), stack.max()), (Some(&1), Some(&4)));
+/// stack.push(&0);
+/// assert_eq!(stack.min(), Some(&0));
+/// stack.pop();
+/// stack.pop();
+/// assert_eq!((stack.min(), stack.max()), (Some(&1), Some(&3)));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MinMaxStack<T> {
+    items: Vec<T>,
+    /// Positions of the minimum and maximum of items[..=i], for each i
+    extremes: Vec<(usize, usize)>
+}
+
+impl<T> MinMaxStack<T> where T : Ord {
+    pub fn new() -> MinMaxStack<T> {
+        MinMaxStack::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity:usize) -> MinMaxStack<T> {
+        MinMaxStack { items: Vec::with_capacity(capacity), extremes: Vec::with_capacity(capacity) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    pub fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.items.last()
+    }
+
+    /// Return the smallest element, the lowest one in the stack among equals
+    pub fn min(&self) -> Option<&T> {
+        self.extremes.last().map(|&(min, _)| &self.items[min])
+    }
+
+    /// Return the largest element, the lowest one in the stack among equals
+    pub fn max(&self) -> Option<&T> {
+        self.extremes.last().map(|&(_, max)| &self.items[max])
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.extremes.clear();
+    }
+
+    /// Remove every element, yielding them top to bottom as pop would
+    pub fn drain(&mut self) -> Rev<vec::Drain<'_, T>> {
+        self.extremes.clear();
+        self.items.drain(..).rev()
+    }
+
+    /// Iterate over the elements bottom to top
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    fn push_value(&mut self, data:T) {
+        let top=self.items.len();
+        let extremes=match self.extremes.last() {
+            Some(&(min, max)) => (
+                if data < self.items[min] { top } else { min },
+                if data > self.items[max] { top } else { max }
+            ),
+            None => (top, top)
+        };
+        self.items.push(data);
+        self.extremes.push(extremes);
+    }
+}
+
+impl<T> Push<T> for MinMaxStack<T> where T : Clone + Ord {
+    fn push(&mut self, data:&T) {
+        self.push_value(data.clone());
+    }
+}
+
+impl<T> Pop<T> for MinMaxStack<T> where T : Clone + Ord {
+    fn pop(&mut self) -> Option<T> {
+        self.extremes.pop();
+        self.items.pop()
+    }
+}
+
+impl<T> Default for MinMaxStack<T> where T : Ord {
+    fn default() -> MinMaxStack<T> {
+        MinMaxStack::new()
+    }
+}
+
+impl<T> FromIterator<T> for MinMaxStack<T> where T : Ord {
+    fn from_iter<I: IntoIterator<Item=T>>(iter:I) -> MinMaxStack<T> {
+        let mut stack=MinMaxStack::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+impl<T> Extend<T> for MinMaxStack<T> where T : Ord {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter:I) {
+        for data in iter {
+            self.push_value(data);
+        }
+    }
+}
+
+impl<T> IntoIterator for MinMaxStack<T> {
+    type Item=T;
+    type IntoIter=vec::IntoIter<T>;
+    fn into_iter(self) -> vec::IntoIter<T> {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a MinMaxStack<T> {
+    type Item=&'a T;
+    type IntoIter=slice::Iter<'a, T>;
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.items.iter()
+    }
+}
+
+impl<T> fmt::Debug for MinMaxStack<T> where T : fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.items.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayStack, MinMaxStack};
+    use data_structures::stack::{Stack, Push, Pop};
+    extern crate rand;
+    use self::rand::{Rng, SeedableRng};
+    use self::rand::rngs::StdRng;
+
+    #[test]
+    fn test_push_peek_and_pop() {
+        let mut stack=ArrayStack::<u32>::with_capacity(4);
+        assert!(stack.capacity() >= 4);
+        assert_eq!(stack.peek(), None);
+        assert_eq!(stack.pop(), None);
+        for i in 0..10 {
+            stack.push(&i);
+            assert_eq!(stack.peek(), Some(&i));
+        }
+        *stack.peek_mut().unwrap()+=10;
+        assert_eq!(stack.size(), 10);
+        assert_eq!(stack.pop(), Some(19));
+        assert_eq!(stack.pop(), Some(8));
+        assert_eq!(stack.as_slice(), &[0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_drain_in_pop_order() {
+        let mut stack:ArrayStack<u32>=(0..5).collect();
+        for value in &mut stack {
+            *value*=2;
+        }
+        assert_eq!(stack.drain().collect::<Vec<u32>>(), vec![8, 6, 4, 2, 0]);
+        assert!(stack.is_empty());
+        stack.extend(vec![1, 2]);
+        assert_eq!(stack.clone().into_iter().collect::<Vec<u32>>(), vec![1, 2]);
+        assert_eq!(format!("{:?}", stack), "ArrayStack { items: [1, 2] }");
+    }
+
+    #[test]
+    fn test_same_pops_as_the_linked_stack() {
+        let mut array_stack=ArrayStack::new();
+        let mut stack=Stack::new();
+        for i in 0..100u32 {
+            if i % 3 == 2 {
+                assert_eq!(array_stack.pop(), stack.pop());
+            } else {
+                array_stack.push(&i);
+                stack.push(&i);
+            }
+        }
+        assert!(array_stack.iter().eq(stack.iter()));
+    }
+
+    #[test]
+    fn test_min_max_after_every_push_and_pop() {
+        let mut rng=StdRng::from_seed([24u8; 32]);
+        let mut stack=MinMaxStack::new();
+        let mut model:Vec<u8>=Vec::new();
+        assert_eq!((stack.min(), stack.max()), (None, None));
+        for _ in 0..2000 {
+            if model.is_empty() || rng.gen::<u8>() < 150 {
+                let value=rng.gen::<u8>() % 32;
+                stack.push(&value);
+                model.push(value);
+            } else {
+                assert_eq!(stack.pop(), model.pop());
+            }
+            assert_eq!(stack.min(), model.iter().min());
+            assert_eq!(stack.max(), model.iter().max());
+            assert_eq!(stack.peek(), model.last());
+        }
+    }
+
+    #[test]
+    fn test_min_max_stack_drain_and_collect() {
+        let mut stack:MinMaxStack<i32>=vec![2, -1, 5, -1].into_iter().collect();
+        assert_eq!((stack.min(), stack.max(), stack.size()), (Some(&-1), Some(&5), 4));
+        assert_eq!(stack.drain().collect::<Vec<i32>>(), vec![-1, 5, -1, 2]);
+        assert_eq!((stack.min(), stack.max()), (None, None));
+        stack.extend(vec![7, 3]);
+        assert_eq!((stack.min(), stack.max()), (Some(&3), Some(&7)));
+        assert_eq!(format!("{:?}", stack), "[7, 3]");
+        assert_eq!(stack.clone(), stack);
+    }
+}
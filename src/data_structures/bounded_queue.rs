@@ -0,0 +1,409 @@
+use std::fmt;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use data_structures::deque::Deque;
+use data_structures::queue::{Enqueue, Dequeue};
+
+/// Reason an element could not be enqueued, handing the element back
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnqueueError<T> {
+    /// The queue stayed at capacity, either right away for try_enqueue or until the timeout
+    Full(T),
+    Closed(T)
+}
+
+impl<T> EnqueueError<T> {
+    /// Return the element that was not enqueued
+    pub fn into_inner(self) -> T {
+        match self {
+            EnqueueError::Full(data) | EnqueueError::Closed(data) => data
+        }
+    }
+}
+
+impl<T> fmt::Display for EnqueueError<T> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EnqueueError::Full(_) => write!(f, "Queue is full"),
+            EnqueueError::Closed(_) => write!(f, "Queue is closed")
+        }
+    }
+}
+
+/// Reason no element could be dequeued
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DequeueError {
+    /// The queue stayed empty, either right away for try_dequeue or until the timeout
+    Empty,
+    /// The queue is closed and every element left in it has been dequeued
+    Closed
+}
+
+impl fmt::Display for DequeueError {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DequeueError::Empty => write!(f, "Queue is empty"),
+            DequeueError::Closed => write!(f, "Queue is closed")
+        }
+    }
+}
+
+struct State<T> {
+    items:Deque<T>,
+    closed:bool
+}
+
+/// Queue holding at most capacity elements, shared between threads that wait for room or for elements
+///
+/// Every method takes &self, so producers and consumers share the queue through an Arc. Once closed,
+/// enqueueing fails while dequeueing drains the elements left before failing too.
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use mldsa_rust::data_structures::bounded_queue::BoundedQueue;
+/// let queue=Arc::new(BoundedQueue::new(2));
+/// let producer={
+///     let queue=queue.clone();
+///     thread::spawn(move || {
+///         for i in 0..10 {
+///             queue.enqueue(i).unwrap();
+///         }
+///         queue.close();
+///     })
+/// };
+/// let mut received=Vec::new();
+/// while let Ok(i)=queue.dequeue() {
+///     received.push(i);
+/// }
+/// producer.join().unwrap();
+/// assert_eq!(received, (0..10).collect::<Vec<i32>>());
+/// ```
+pub struct BoundedQueue<T> {
+    state:Mutex<State<T>>,
+    not_empty:Condvar,
+    not_full:Condvar,
+    capacity:usize
+}
+
+impl<T> BoundedQueue<T> {
+    /// Construct an open queue holding at most capacity elements, which must be positive
+    pub fn new(capacity:usize) -> BoundedQueue<T> {
+        assert!(capacity > 0, "Capacity must be positive");
+        BoundedQueue {
+            state: Mutex::new(State { items: Deque::with_capacity(capacity), closed: false }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn size(&self) -> usize {
+        self.lock().items.size()
+    }
+
+    pub fn empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// Refuse any further element and wake every thread waiting on the queue
+    pub fn close(&self) {
+        self.lock().closed=true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    /// Wait until there is room for the element and add it at the back
+    pub fn enqueue(&self, data:T) -> Result<(), EnqueueError<T>> {
+        let state=self.lock();
+        let state=self.not_full.wait_while(state, |state| self.is_full(state)).unwrap_or_else(PoisonError::into_inner);
+        self.push(state, data)
+    }
+
+    /// Like enqueue, but fail with Full when there is still no room after the timeout
+    pub fn enqueue_timeout(&self, data:T, timeout:Duration) -> Result<(), EnqueueError<T>> {
+        let state=self.lock();
+        let (state, _)=self.not_full.wait_timeout_while(state, timeout, |state| self.is_full(state)).unwrap_or_else(PoisonError::into_inner);
+        self.push(state, data)
+    }
+
+    /// Add the element at the back without waiting
+    pub fn try_enqueue(&self, data:T) -> Result<(), EnqueueError<T>> {
+        let state=self.lock();
+        self.push(state, data)
+    }
+
+    /// Wait until there is an element and remove it from the front
+    pub fn dequeue(&self) -> Result<T, DequeueError> {
+        let state=self.lock();
+        let state=self.not_empty.wait_while(state, |state| Self::is_waiting_for_data(state)).unwrap_or_else(PoisonError::into_inner);
+        self.pop(state)
+    }
+
+    /// Like dequeue, but fail with Empty when there is still no element after the timeout
+    pub fn dequeue_timeout(&self, timeout:Duration) -> Result<T, DequeueError> {
+        let state=self.lock();
+        let (state, _)=self.not_empty.wait_timeout_while(state, timeout, |state| Self::is_waiting_for_data(state)).unwrap_or_else(PoisonError::into_inner);
+        self.pop(state)
+    }
+
+    /// Remove the element at the front without waiting
+    pub fn try_dequeue(&self) -> Result<T, DequeueError> {
+        let state=self.lock();
+        self.pop(state)
+    }
+
+    /// A panic while the lock is held cannot leave the state half updated, so a poisoned lock is still usable
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_full(&self, state:&mut State<T>) -> bool {
+        !state.closed && state.items.size() >= self.capacity
+    }
+
+    fn is_waiting_for_data(state:&mut State<T>) -> bool {
+        !state.closed && state.items.empty()
+    }
+
+    fn push(&self, mut state:MutexGuard<'_, State<T>>, data:T) -> Result<(), EnqueueError<T>> {
+        if state.closed {
+            return Err(EnqueueError::Closed(data));
+        }
+        if state.items.size() >= self.capacity {
+            return Err(EnqueueError::Full(data));
+        }
+        state.items.push_back(data);
+        drop(state);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    fn pop(&self, mut state:MutexGuard<'_, State<T>>) -> Result<T, DequeueError> {
+        match state.items.pop_front() {
+            Some(data) => {
+                drop(state);
+                self.not_full.notify_one();
+                Ok(data)
+            },
+            None if state.closed => Err(DequeueError::Closed),
+            None => Err(DequeueError::Empty)
+        }
+    }
+}
+
+/// With exclusive access no other thread can make room, so enqueueing into a full queue panics instead of
+/// waiting forever; the element is dropped if the queue is closed
+impl<T> Enqueue<T> for BoundedQueue<T> where T : Clone {
+    fn enqueue(&mut self, data:&T) {
+        if let Err(EnqueueError::Full(_))=self.try_enqueue(data.clone()) {
+            panic!("Queue is full");
+        }
+    }
+}
+
+/// Returns None when the queue is empty, as no other thread can add an element
+impl<T> Dequeue<T> for BoundedQueue<T> where T : Clone {
+    fn dequeue(&mut self) -> Option<T> {
+        self.try_dequeue().ok()
+    }
+}
+
+/// Waits for room like BoundedQueue::enqueue, and drops the element if the queue is closed
+impl<T> Enqueue<T> for &BoundedQueue<T> where T : Clone {
+    fn enqueue(&mut self, data:&T) {
+        let _=BoundedQueue::enqueue(self, data.clone());
+    }
+}
+
+/// Waits for an element like BoundedQueue::dequeue, returning None once the queue is closed and drained
+impl<T> Dequeue<T> for &BoundedQueue<T> where T : Clone {
+    fn dequeue(&mut self) -> Option<T> {
+        BoundedQueue::dequeue(self).ok()
+    }
+}
+
+impl<T> fmt::Debug for BoundedQueue<T> where T : fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        let state=self.lock();
+        f.debug_struct("BoundedQueue")
+            .field("items", &state.items)
+            .field("capacity", &self.capacity)
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedQueue, EnqueueError, DequeueError};
+    use data_structures::queue::{Enqueue, Dequeue};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn it_fails_without_waiting_when_full_or_empty() {
+        let queue=BoundedQueue::new(2);
+        assert_eq!(queue.try_dequeue(), Err(DequeueError::Empty));
+        assert_eq!(queue.try_enqueue(1), Ok(()));
+        assert_eq!(queue.try_enqueue(2), Ok(()));
+        assert_eq!(queue.try_enqueue(3), Err(EnqueueError::Full(3)));
+        assert_eq!(queue.size(), 2);
+        assert_eq!(queue.try_dequeue(), Ok(1));
+        assert_eq!(queue.try_enqueue(3), Ok(()));
+        assert_eq!(queue.try_dequeue(), Ok(2));
+        assert_eq!(queue.try_dequeue(), Ok(3));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn it_times_out() {
+        let queue=BoundedQueue::new(1);
+        let timeout=Duration::from_millis(20);
+        let start=Instant::now();
+        assert_eq!(queue.dequeue_timeout(timeout), Err(DequeueError::Empty));
+        assert!(start.elapsed() >= timeout);
+        queue.enqueue(1).unwrap();
+        let start=Instant::now();
+        assert_eq!(queue.enqueue_timeout(2, timeout).unwrap_err().into_inner(), 2);
+        assert!(start.elapsed() >= timeout);
+        assert_eq!(queue.dequeue_timeout(timeout), Ok(1));
+    }
+
+    #[test]
+    fn it_drains_after_close() {
+        let queue=BoundedQueue::new(4);
+        queue.enqueue(1).unwrap();
+        queue.enqueue(2).unwrap();
+        queue.close();
+        assert!(queue.is_closed());
+        assert_eq!(queue.try_enqueue(3), Err(EnqueueError::Closed(3)));
+        assert_eq!(queue.enqueue(3), Err(EnqueueError::Closed(3)));
+        assert_eq!(queue.dequeue(), Ok(1));
+        assert_eq!(queue.dequeue_timeout(Duration::from_secs(10)), Ok(2));
+        assert_eq!(queue.dequeue(), Err(DequeueError::Closed));
+        assert_eq!(queue.try_dequeue(), Err(DequeueError::Closed));
+    }
+
+    #[test]
+    fn it_wakes_every_waiter_on_close() {
+        let empty=Arc::new(BoundedQueue::<i32>::new(1));
+        let full=Arc::new(BoundedQueue::new(1));
+        full.enqueue(0).unwrap();
+        let mut handles=Vec::new();
+        for _ in 0..3 {
+            let empty=empty.clone();
+            handles.push(thread::spawn(move || empty.dequeue().is_err()));
+            let full=full.clone();
+            handles.push(thread::spawn(move || full.enqueue_timeout(1, Duration::from_secs(60)).is_err()));
+        }
+        thread::sleep(Duration::from_millis(20));
+        empty.close();
+        full.close();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn it_passes_every_element_from_producers_to_consumers() {
+        let queue=Arc::new(BoundedQueue::new(8));
+        let producers:Vec<_>=(0..4u64).map(|producer| {
+            let queue=queue.clone();
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    queue.enqueue(producer*1000+i).unwrap();
+                }
+            })
+        }).collect();
+        let consumers:Vec<_>=(0..3).map(|_| {
+            let queue=queue.clone();
+            thread::spawn(move || {
+                let mut received=Vec::new();
+                while let Ok(data)=queue.dequeue() {
+                    assert!(queue.size() <= 8);
+                    received.push(data);
+                }
+                received
+            })
+        }).collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        queue.close();
+        let mut received:Vec<u64>=consumers.into_iter().flat_map(|consumer| consumer.join().unwrap()).collect();
+        received.sort();
+        assert_eq!(received, (0..4000).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn it_implements_the_queue_traits() {
+        let mut queue=BoundedQueue::new(3);
+        Enqueue::enqueue(&mut queue, &1);
+        Enqueue::enqueue(&mut queue, &2);
+        assert_eq!(Dequeue::dequeue(&mut queue), Some(1));
+        assert_eq!(Dequeue::dequeue(&mut queue), Some(2));
+        assert_eq!(Dequeue::dequeue(&mut queue), None);
+        queue.close();
+        Enqueue::enqueue(&mut queue, &3);
+        assert_eq!(Dequeue::dequeue(&mut queue), None);
+        assert_eq!(format!("{:?}", queue), "BoundedQueue { items: [], capacity: 3, closed: true }");
+    }
+
+    #[test]
+    #[should_panic(expected = "Queue is full")]
+    fn it_panics_when_enqueueing_into_a_full_queue_with_exclusive_access() {
+        let mut queue=BoundedQueue::new(1);
+        Enqueue::enqueue(&mut queue, &1);
+        Enqueue::enqueue(&mut queue, &2);
+    }
+
+    #[test]
+    fn it_implements_the_queue_traits_for_a_shared_reference() {
+        fn forward<Q, R>(mut from:Q, mut to:R) where Q : Dequeue<u32>, R : Enqueue<u32> {
+            while let Some(data)=from.dequeue() {
+                to.enqueue(&data);
+            }
+        }
+        let input=Arc::new(BoundedQueue::new(2));
+        let output=Arc::new(BoundedQueue::new(2));
+        let worker={
+            let (input, output)=(input.clone(), output.clone());
+            thread::spawn(move || {
+                forward(&*input, &*output);
+                output.close();
+            })
+        };
+        let producer={
+            let input=input.clone();
+            thread::spawn(move || {
+                for i in 0..100 {
+                    input.enqueue(i).unwrap();
+                }
+                input.close();
+            })
+        };
+        let mut received=Vec::new();
+        let mut output_ref=&*output;
+        while let Some(data)=Dequeue::dequeue(&mut output_ref) {
+            received.push(data);
+        }
+        producer.join().unwrap();
+        worker.join().unwrap();
+        assert_eq!(received, (0..100).collect::<Vec<u32>>());
+    }
+}
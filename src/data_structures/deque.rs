@@ -0,0 +1,571 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::mem::MaybeUninit;
+use std::ops::{Index, IndexMut};
+use std::slice;
+use data_structures::queue::{Enqueue, Dequeue};
+
+/// Double-ended queue stored in a growable ring buffer
+///
+/// The elements occupy the slots from head onwards, wrapping around the end of the buffer, so pushing
+/// and popping at either end moves no other element and only allocates when the buffer is full.
+pub struct Deque<T> {
+    /// Every slot is allocated; exactly the size slots starting at head hold initialized elements
+    buffer: Vec<MaybeUninit<T>>,
+    head: usize,
+    size: usize
+}
+
+const MIN_CAPACITY: usize = 4;
+
+/// Iterator over references to the elements, from either end
+pub struct Iter<'a, T: 'a> {
+    front:slice::Iter<'a, T>,
+    back:slice::Iter<'a, T>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item=&'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining=self.front.len()+self.back.len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// Iterator over mutable references to the elements, from either end
+pub struct IterMut<'a, T: 'a> {
+    front:slice::IterMut<'a, T>,
+    back:slice::IterMut<'a, T>
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item=&'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining=self.front.len()+self.back.len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+/// Iterator that moves the elements out of the deque, from either end
+pub struct IntoIter<T> {
+    deque:Deque<T>
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item=T;
+    fn next(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.size, Some(self.deque.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> Deque<T> {
+    pub fn new() -> Deque<T> {
+        Deque::with_capacity(0)
+    }
+
+    /// Construct an empty deque with room for capacity elements before it reallocates
+    pub fn with_capacity(capacity:usize) -> Deque<T> {
+        let mut deque=Deque { buffer: Vec::new(), head: 0, size: 0 };
+        deque.set_capacity(capacity);
+        deque
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Return the number of elements the deque holds before it reallocates
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Make room for at least additional more elements
+    pub fn reserve(&mut self, additional:usize) {
+        let required=self.size.checked_add(additional).expect("Capacity overflow");
+        if required > self.capacity() {
+            self.set_capacity(required.max(2*self.capacity()));
+        }
+    }
+
+    /// Release the slots that do not hold an element
+    pub fn shrink_to_fit(&mut self) {
+        let size=self.size;
+        self.set_capacity(size);
+    }
+
+    pub fn push_back(&mut self, data:T) {
+        self.grow_if_full();
+        let slot=self.slot(self.size);
+        self.buffer[slot]=MaybeUninit::new(data);
+        self.size+=1;
+    }
+
+    pub fn push_front(&mut self, data:T) {
+        self.grow_if_full();
+        self.head=(self.head+self.capacity()-1) % self.capacity();
+        self.buffer[self.head]=MaybeUninit::new(data);
+        self.size+=1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+        // The head slot is initialized, and moving head past it marks it as vacant so it is never read again
+        let data=unsafe { self.buffer[self.head].assume_init_read() };
+        self.head=(self.head+1) % self.capacity();
+        self.size-=1;
+        Some(data)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+        self.size-=1;
+        let slot=self.slot(self.size);
+        Some(unsafe { self.buffer[slot].assume_init_read() })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.size.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.size.checked_sub(1).and_then(move |index| self.get_mut(index))
+    }
+
+    /// Return the element at the given position from the front
+    pub fn get(&self, index:usize) -> Option<&T> {
+        if index >= self.size {
+            return None;
+        }
+        Some(unsafe { self.buffer[self.slot(index)].assume_init_ref() })
+    }
+
+    pub fn get_mut(&mut self, index:usize) -> Option<&mut T> {
+        if index >= self.size {
+            return None;
+        }
+        let slot=self.slot(index);
+        Some(unsafe { self.buffer[slot].assume_init_mut() })
+    }
+
+    /// Drop every element, keeping the capacity
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+        self.head=0;
+    }
+
+    /// Return the elements as the part from head up to the end of the buffer and the part that wrapped around
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (front, back)=self.ranges();
+        let buffer=self.buffer.as_ptr() as *const T;
+        // Both ranges cover initialized slots only and do not overlap
+        unsafe {
+            (slice::from_raw_parts(buffer.add(front.0), front.1), slice::from_raw_parts(buffer, back))
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front, back)=self.ranges();
+        let buffer=self.buffer.as_mut_ptr() as *mut T;
+        unsafe {
+            (slice::from_raw_parts_mut(buffer.add(front.0), front.1), slice::from_raw_parts_mut(buffer, back))
+        }
+    }
+
+    /// Rotate the buffer if the elements wrap around its end, so that they sit in one slice, and return that slice
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::deque::Deque;
+    /// let mut deque=Deque::with_capacity(4);
+    /// deque.push_back(2);
+    /// deque.push_back(3);
+    /// deque.push_front(1);
+    /// assert_eq!(deque.as_slices(), (&[1][..], &[2, 3][..]));
+    /// deque.make_contiguous().sort_by(|a, b| b.cmp(a));
+    /// assert_eq!(deque.as_slices(), (&[3, 2, 1][..], &[][..]));
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if self.head+self.size > self.capacity() {
+            let head=self.head;
+            self.buffer.rotate_left(head);
+            self.head=0;
+        }
+        self.as_mut_slices().0
+    }
+
+    /// Iterate over references to the elements, from the front or with rev() from the back
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back)=self.as_slices();
+        Iter { front: front.iter(), back: back.iter() }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back)=self.as_mut_slices();
+        IterMut { front: front.iter_mut(), back: back.iter_mut() }
+    }
+
+    /// Return the buffer slot of the element at the given position from the front
+    fn slot(&self, index:usize) -> usize {
+        let slot=self.head+index;
+        if slot >= self.capacity() { slot-self.capacity() } else { slot }
+    }
+
+    /// Return the start and length of the part up to the end of the buffer, and the length of the wrapped part
+    fn ranges(&self) -> ((usize, usize), usize) {
+        let to_end=self.capacity()-self.head;
+        if self.size <= to_end {
+            ((self.head, self.size), 0)
+        } else {
+            ((self.head, to_end), self.size-to_end)
+        }
+    }
+
+    fn grow_if_full(&mut self) {
+        if self.size == self.capacity() {
+            let capacity=(2*self.capacity()).max(MIN_CAPACITY);
+            self.set_capacity(capacity);
+        }
+    }
+
+    /// Move the elements to the front of a buffer of exactly capacity slots, which must hold them all
+    fn set_capacity(&mut self, capacity:usize) {
+        debug_assert!(capacity >= self.size);
+        // Rotating the whole buffer brings head to slot 0 whether the elements wrap or not, and also
+        // when there are none, so the slots cut off below never hold an element
+        let head=self.head;
+        self.buffer.rotate_left(head);
+        self.head=0;
+        self.buffer.truncate(capacity);
+        self.buffer.resize_with(capacity, MaybeUninit::uninit);
+        self.buffer.shrink_to_fit();
+    }
+}
+
+impl<T> Drop for Deque<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Index<usize> for Deque<T> {
+    type Output=T;
+    fn index(&self, index:usize) -> &T {
+        self.get(index).expect("Index out of range")
+    }
+}
+
+impl<T> IndexMut<usize> for Deque<T> {
+    fn index_mut(&mut self, index:usize) -> &mut T {
+        self.get_mut(index).expect("Index out of range")
+    }
+}
+
+impl<T> Enqueue<T> for Deque<T> where T : Clone {
+    fn enqueue(&mut self, data:&T) {
+        self.push_back(data.clone());
+    }
+}
+
+impl<T> Dequeue<T> for Deque<T> where T : Clone {
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+}
+
+impl<T> IntoIterator for Deque<T> {
+    type Item=T;
+    type IntoIter=IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Deque<T> {
+    type Item=&'a T;
+    type IntoIter=Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Deque<T> {
+    type Item=&'a mut T;
+    type IntoIter=IterMut<'a, T>;
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter:I) -> Deque<T> {
+        let mut deque=Deque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T> Extend<T> for Deque<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter:I) {
+        let iter=iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for data in iter {
+            self.push_back(data);
+        }
+    }
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Deque<T> {
+        Deque::new()
+    }
+}
+
+impl<T> Clone for Deque<T> where T : Clone {
+    fn clone(&self) -> Deque<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> fmt::Debug for Deque<T> where T : fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for Deque<T> where T : PartialEq {
+    fn eq(&self, other:&Deque<T>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for Deque<T> where T : Eq {}
+
+#[cfg(test)]
+mod tests {
+    use super::Deque;
+    use data_structures::queue::{Queue, Enqueue, Dequeue};
+    use std::rc::Rc;
+
+    fn assert_deque(deque:&Deque<i32>, expected:&[i32]) {
+        assert_eq!(deque.iter().cloned().collect::<Vec<i32>>(), expected.to_vec());
+        assert_eq!(deque.iter().rev().cloned().collect::<Vec<i32>>(), expected.iter().rev().cloned().collect::<Vec<i32>>());
+        assert_eq!(deque.size(), expected.len());
+        for (index, value) in expected.iter().enumerate() {
+            assert_eq!(deque[index], *value);
+        }
+        assert_eq!(deque.get(expected.len()), None);
+    }
+
+    #[test]
+    fn it_pushes_and_pops_at_both_ends_across_the_wrap() {
+        let mut deque=Deque::with_capacity(4);
+        for i in 0..3 {
+            deque.push_back(i);
+        }
+        assert_eq!(deque.pop_front(), Some(0));
+        assert_eq!(deque.pop_front(), Some(1));
+        deque.push_back(3);
+        deque.push_back(4);
+        deque.push_front(1);
+        assert_eq!(deque.capacity(), 4);
+        assert_deque(&deque, &[1, 2, 3, 4]);
+        deque.push_front(0);
+        assert!(deque.capacity() > 4);
+        assert_deque(&deque, &[0, 1, 2, 3, 4]);
+        assert_eq!((deque.front(), deque.back()), (Some(&0), Some(&4)));
+        assert_eq!(deque.pop_back(), Some(4));
+        assert_eq!(deque.pop_front(), Some(0));
+        assert_deque(&deque, &[1, 2, 3]);
+        while deque.pop_back().is_some() {}
+        assert_eq!((deque.pop_front(), deque.front(), deque.back()), (None, None, None));
+    }
+
+    #[test]
+    fn it_indexes_and_mutates_in_place() {
+        let mut deque:Deque<i32>=(0..6).collect();
+        deque.pop_front();
+        deque.push_back(6);
+        deque[0]+=10;
+        *deque.back_mut().unwrap()+=10;
+        for value in deque.iter_mut().rev().take(1) {
+            *value+=1;
+        }
+        assert_deque(&deque, &[11, 2, 3, 4, 5, 17]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of range")]
+    fn it_panics_on_an_index_out_of_range() {
+        let deque:Deque<i32>=(0..3).collect();
+        let _=deque[3];
+    }
+
+    #[test]
+    fn it_makes_the_elements_contiguous() {
+        let mut deque=Deque::with_capacity(8);
+        for i in 0..6 {
+            deque.push_back(i);
+        }
+        for _ in 0..5 {
+            let value=deque.pop_front().unwrap();
+            deque.push_back(value);
+        }
+        assert!(!deque.as_slices().1.is_empty());
+        assert_eq!(deque.make_contiguous(), &[5, 0, 1, 2, 3, 4]);
+        assert!(deque.as_slices().1.is_empty());
+        assert_deque(&deque, &[5, 0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn it_manages_capacity() {
+        let mut deque:Deque<i32>=Deque::new();
+        assert_eq!(deque.capacity(), 0);
+        deque.reserve(10);
+        assert!(deque.capacity() >= 10);
+        deque.extend(0..3);
+        deque.push_front(-1);
+        deque.shrink_to_fit();
+        assert_eq!(deque.capacity(), 4);
+        assert_deque(&deque, &[-1, 0, 1, 2]);
+        deque.clear();
+        assert_eq!(deque.capacity(), 4);
+        assert_deque(&deque, &[]);
+    }
+
+    #[test]
+    fn it_shrinks_with_the_elements_away_from_the_first_slot() {
+        let mut deque=Deque::with_capacity(8);
+        deque.extend(0..6);
+        for _ in 0..4 {
+            deque.pop_front();
+        }
+        deque.shrink_to_fit();
+        assert_eq!(deque.capacity(), 2);
+        assert_deque(&deque, &[4, 5]);
+        deque.push_front(3);
+        assert_deque(&deque, &[3, 4, 5]);
+
+        let mut deque=Deque::with_capacity(8);
+        deque.extend(0..8);
+        for _ in 0..6 {
+            let value=deque.pop_front().unwrap();
+            deque.push_back(value);
+        }
+        deque.pop_front();
+        deque.shrink_to_fit();
+        assert_eq!(deque.capacity(), 7);
+        assert_deque(&deque, &[7, 0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn it_shrinks_an_emptied_deque() {
+        let mut deque=Deque::new();
+        deque.push_back(1);
+        deque.pop_front();
+        deque.shrink_to_fit();
+        assert_eq!(deque.capacity(), 0);
+        deque.push_back(2);
+        deque.push_front(1);
+        assert_deque(&deque, &[1, 2]);
+    }
+
+    #[test]
+    fn it_drops_every_element() {
+        let shared=Rc::new(0);
+        let mut deque:Deque<Rc<i32>>=(0..10).map(|_| shared.clone()).collect();
+        deque.pop_front();
+        deque.push_front(shared.clone());
+        assert_eq!(Rc::strong_count(&shared), 11);
+        drop(deque);
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn it_serves_as_a_queue() {
+        let mut deque=Deque::new();
+        for i in 0..10 {
+            deque.enqueue(&i);
+        }
+        assert_eq!(deque.dequeue(), Some(0));
+        assert_eq!(deque.clone().into_iter().collect::<Vec<i32>>(), (1..10).collect::<Vec<i32>>());
+        assert_eq!(format!("{:?}", deque.iter().take(2).collect::<Vec<&i32>>()), "[1, 2]");
+        assert_eq!(deque, deque.clone());
+    }
+
+    extern crate test;
+    use self::test::Bencher;
+
+    #[bench]
+    fn bench_deque_enqueue_dequeue(b: &mut Bencher) {
+        let mut deque:Deque<u32>=(0..1000).collect();
+        b.iter(|| {
+            let value=deque.dequeue().unwrap();
+            deque.enqueue(&value);
+        });
+    }
+
+    #[bench]
+    fn bench_queue_enqueue_dequeue(b: &mut Bencher) {
+        let mut queue:Queue<u32>=(0..1000).collect();
+        b.iter(|| {
+            let value=queue.dequeue().unwrap();
+            queue.enqueue(&value);
+        });
+    }
+}
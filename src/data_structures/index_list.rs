@@ -0,0 +1,592 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_LIST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Return an id no list has had before
+fn next_list_id() -> u64 {
+    NEXT_LIST_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Stable handle to an element of an `IndexList`
+///
+/// The generation tells a handle to a removed element apart from one to a later element that reuses its slot,
+/// and the list id a handle of one list apart from a handle of another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    list: u64,
+    index: usize,
+    generation: u64
+}
+
+/// A slot of the arena: a linked element when data is set, otherwise a member of the free list chained through next
+#[derive(Clone)]
+struct Entry<T> {
+    generation: u64,
+    prev: Option<usize>,
+    next: Option<usize>,
+    data: Option<T>
+}
+
+/// Doubly linked list whose nodes live in one vector and link to each other by index
+///
+/// Removed slots are chained into a free list and reused by the next insert, so a list that grows and
+/// shrinks allocates no more than its largest size, and every operation on a `NodeId` is O(1).
+pub struct IndexList<T> {
+    /// Stamped on the handles of this list, and new for every clone
+    id: u64,
+    entries: Vec<Entry<T>>,
+    free: Option<usize>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    size: usize
+}
+
+/// Iterator over references to the elements, from either end
+pub struct Iter<'a, T: 'a> {
+    entries:&'a [Entry<T>],
+    next_index:Option<usize>,
+    back_index:Option<usize>,
+    remaining:usize
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item=&'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let entry=&self.entries[self.next_index?];
+        self.next_index=entry.next;
+        self.remaining-=1;
+        entry.data.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let entry=&self.entries[self.back_index?];
+        self.back_index=entry.prev;
+        self.remaining-=1;
+        entry.data.as_ref()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// Iterator over mutable references to the elements, from either end
+pub struct IterMut<'a, T: 'a> {
+    entries:*mut Entry<T>,
+    next_index:Option<usize>,
+    back_index:Option<usize>,
+    remaining:usize,
+    marker:PhantomData<&'a mut Entry<T>>
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item=&'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // The list stays mutably borrowed for 'a and the count stops both ends before they meet,
+        // so every linked entry is handed out once and the references never alias
+        let entry=unsafe { &mut *self.entries.add(self.next_index?) };
+        self.next_index=entry.next;
+        self.remaining-=1;
+        entry.data.as_mut()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let entry=unsafe { &mut *self.entries.add(self.back_index?) };
+        self.back_index=entry.prev;
+        self.remaining-=1;
+        entry.data.as_mut()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+/// Iterator that moves the elements out of the list, from either end
+pub struct IntoIter<T> {
+    list:IndexList<T>
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item=T;
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IndexList<T> {
+    pub fn new() -> IndexList<T> {
+        IndexList::with_capacity(0)
+    }
+
+    /// Construct an empty list with room for capacity elements before it reallocates
+    pub fn with_capacity(capacity:usize) -> IndexList<T> {
+        IndexList {
+            id: next_list_id(),
+            entries: Vec::with_capacity(capacity),
+            free: None,
+            head: None,
+            tail: None,
+            size: 0
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Return the number of elements the list holds before it reallocates
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    pub fn empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Iterate over references to the elements, from the front or with rev() from the back
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { entries: &self.entries, next_index: self.head, back_index: self.tail, remaining: self.size }
+    }
+
+    /// Iterate over mutable references to the elements
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { entries: self.entries.as_mut_ptr(), next_index: self.head, back_index: self.tail, remaining: self.size, marker: PhantomData }
+    }
+
+    pub fn count_front(&self) -> usize {
+        let mut index=self.head;
+        let mut count=0;
+        while let Some(current)=index {
+            count+=1;
+            index=self.entries[current].next;
+        }
+        count
+    }
+
+    pub fn count_back(&self) -> usize {
+        let mut index=self.tail;
+        let mut count=0;
+        while let Some(current)=index {
+            count+=1;
+            index=self.entries[current].prev;
+        }
+        count
+    }
+
+    /// Return the handle of the front element
+    pub fn front_id(&self) -> Option<NodeId> {
+        self.head.map(|index| self.id_of(index))
+    }
+
+    /// Return the handle of the back element
+    pub fn back_id(&self) -> Option<NodeId> {
+        self.tail.map(|index| self.id_of(index))
+    }
+
+    /// Return the handle of the element after the given one
+    pub fn next_id(&self, id:NodeId) -> Option<NodeId> {
+        self.checked_index(id).and_then(|index| self.entries[index].next).map(|index| self.id_of(index))
+    }
+
+    /// Return the handle of the element before the given one
+    pub fn prev_id(&self, id:NodeId) -> Option<NodeId> {
+        self.checked_index(id).and_then(|index| self.entries[index].prev).map(|index| self.id_of(index))
+    }
+
+    /// Return the element of the handle, or None once it has been removed or when the handle is of another list
+    pub fn get(&self, id:NodeId) -> Option<&T> {
+        self.checked_index(id).and_then(|index| self.entries[index].data.as_ref())
+    }
+
+    pub fn get_mut(&mut self, id:NodeId) -> Option<&mut T> {
+        self.checked_index(id).and_then(move |index| self.entries[index].data.as_mut())
+    }
+
+    pub fn insert_back(&mut self, data:T) -> NodeId {
+        let tail=self.tail;
+        self.link(tail, None, data)
+    }
+
+    pub fn insert_front(&mut self, data:T) -> NodeId {
+        let head=self.head;
+        self.link(None, head, data)
+    }
+
+    /// Insert an element right after the element of the handle
+    pub fn insert_after(&mut self, id:NodeId, data:T) -> Result<NodeId, &'static str> {
+        let index=self.checked_index(id).ok_or("Invalid node")?;
+        let next=self.entries[index].next;
+        Ok(self.link(Some(index), next, data))
+    }
+
+    /// Insert an element right before the element of the handle
+    pub fn insert_before(&mut self, id:NodeId, data:T) -> Result<NodeId, &'static str> {
+        let index=self.checked_index(id).ok_or("Invalid node")?;
+        let prev=self.entries[index].prev;
+        Ok(self.link(prev, Some(index), data))
+    }
+
+    /// Remove the element of the handle in O(1), or return None when it has already been removed
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::index_list::IndexList;
+    /// let mut list=IndexList::new();
+    /// list.insert_back(1);
+    /// let two=list.insert_back(2);
+    /// list.insert_back(3);
+    /// assert_eq!(list.remove(two), Some(2));
+    /// assert_eq!(list.remove(two), None);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), vec![1, 3]);
+    /// ```
+    pub fn remove(&mut self, id:NodeId) -> Option<T> {
+        let index=self.checked_index(id)?;
+        Some(self.unlink(index))
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|index| self.unlink(index))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|index| self.unlink(index))
+    }
+
+    fn id_of(&self, index:usize) -> NodeId {
+        NodeId { list: self.id, index, generation: self.entries[index].generation }
+    }
+
+    fn checked_index(&self, id:NodeId) -> Option<usize> {
+        if id.list != self.id {
+            return None;
+        }
+        match self.entries.get(id.index) {
+            Some(entry) if entry.generation == id.generation && entry.data.is_some() => Some(id.index),
+            _ => None
+        }
+    }
+
+    /// Put the data in a free slot, or a new one, and link it between prev and next, where None stands for the ends of the list
+    fn link(&mut self, prev:Option<usize>, next:Option<usize>, data:T) -> NodeId {
+        let index=match self.free {
+            Some(index) => {
+                self.free=self.entries[index].next;
+                let entry=&mut self.entries[index];
+                entry.prev=prev;
+                entry.next=next;
+                entry.data=Some(data);
+                index
+            },
+            None => {
+                self.entries.push(Entry { generation: 0, prev, next, data: Some(data) });
+                self.entries.len()-1
+            }
+        };
+        match prev {
+            Some(prev) => self.entries[prev].next=Some(index),
+            None => self.head=Some(index)
+        }
+        match next {
+            Some(next) => self.entries[next].prev=Some(index),
+            None => self.tail=Some(index)
+        }
+        self.size+=1;
+        self.id_of(index)
+    }
+
+    /// Unlink the element at the index and hand its slot over to the free list
+    fn unlink(&mut self, index:usize) -> T {
+        let (prev, next)=(self.entries[index].prev, self.entries[index].next);
+        match prev {
+            Some(prev) => self.entries[prev].next=next,
+            None => self.head=next
+        }
+        match next {
+            Some(next) => self.entries[next].prev=prev,
+            None => self.tail=prev
+        }
+        self.size-=1;
+        let entry=&mut self.entries[index];
+        entry.generation+=1;
+        entry.prev=None;
+        entry.next=self.free;
+        self.free=Some(index);
+        entry.data.take().unwrap()
+    }
+}
+
+impl<T> IndexList<T> where T: Clone {
+    pub fn front(&self) -> Result<T, &str> {
+        self.iter().next().cloned().ok_or("No element")
+    }
+
+    pub fn back(&self) -> Result<T, &str> {
+        self.iter().next_back().cloned().ok_or("No element")
+    }
+}
+
+impl<T> Clone for IndexList<T> where T : Clone {
+    fn clone(&self) -> IndexList<T> {
+        IndexList {
+            id: next_list_id(),
+            entries: self.entries.clone(),
+            free: self.free,
+            head: self.head,
+            tail: self.tail,
+            size: self.size
+        }
+    }
+}
+
+impl<T> IntoIterator for IndexList<T> {
+    type Item=T;
+    type IntoIter=IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a IndexList<T> {
+    type Item=&'a T;
+    type IntoIter=Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut IndexList<T> {
+    type Item=&'a mut T;
+    type IntoIter=IterMut<'a, T>;
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for IndexList<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter:I) -> IndexList<T> {
+        let mut list=IndexList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for IndexList<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter:I) {
+        for data in iter {
+            self.insert_back(data);
+        }
+    }
+}
+
+impl<T> Default for IndexList<T> {
+    fn default() -> IndexList<T> {
+        IndexList::new()
+    }
+}
+
+impl<T> fmt::Debug for IndexList<T> where T : fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for IndexList<T> where T : PartialEq {
+    fn eq(&self, other:&IndexList<T>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for IndexList<T> where T : Eq {}
+
+#[cfg(test)]
+mod tests {
+    use super::IndexList;
+    use data_structures::linked_list::LinkedList;
+
+    fn assert_list(list:&IndexList<i32>, expected:&[i32]) {
+        assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), expected.to_vec());
+        assert_eq!(list.iter().rev().count(), expected.len());
+        assert_eq!(list.size(), expected.len());
+        assert_eq!(list.count_front(), expected.len());
+        assert_eq!(list.count_back(), expected.len());
+    }
+
+    #[test]
+    fn it_inserts_and_pops_at_both_ends() {
+        let mut list=IndexList::new();
+        assert_eq!(list.back(), Err("No element"));
+        list.insert_back(2);
+        list.insert_front(1);
+        list.insert_back(3);
+        assert_list(&list, &[1, 2, 3]);
+        assert_eq!((list.front(), list.back()), (Ok(1), Ok(3)));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_front(), None);
+        assert_list(&list, &[]);
+    }
+
+    #[test]
+    fn it_removes_and_inserts_by_handle() {
+        let mut list=IndexList::new();
+        let one=list.insert_back(1);
+        let three=list.insert_back(3);
+        list.insert_after(one, 2).unwrap();
+        list.insert_before(one, 0).unwrap();
+        assert_list(&list, &[0, 1, 2, 3]);
+        assert_eq!(list.remove(one), Some(1));
+        assert_eq!(list.get(one), None);
+        assert!(list.insert_after(one, 5).is_err());
+        *list.get_mut(three).unwrap()=30;
+        assert_list(&list, &[0, 2, 30]);
+        assert_eq!(list.prev_id(three).and_then(|id| list.get(id)), Some(&2));
+        assert_eq!(list.front_id().and_then(|id| list.next_id(id)), list.prev_id(three));
+        assert_eq!(list.remove(list.back_id().unwrap()), Some(30));
+        assert_list(&list, &[0, 2]);
+    }
+
+    #[test]
+    fn it_reuses_freed_slots_without_reviving_stale_handles() {
+        let mut list=IndexList::with_capacity(4);
+        let ids:Vec<_>=(0..4).map(|i| list.insert_back(i)).collect();
+        let capacity=list.capacity();
+        for id in ids.iter() {
+            list.remove(*id);
+        }
+        let reused:Vec<_>=(10..14).map(|i| list.insert_back(i)).collect();
+        assert_eq!(list.capacity(), capacity);
+        for id in ids.iter() {
+            assert_eq!(list.get(*id), None);
+        }
+        assert_eq!(list.get(reused[0]), Some(&10));
+        assert_list(&list, &[10, 11, 12, 13]);
+    }
+
+    #[test]
+    fn it_rejects_handles_of_another_list() {
+        let mut list:IndexList<i32>=(0..3).collect();
+        let mut other:IndexList<i32>=(10..13).collect();
+        let id=list.front_id().unwrap();
+        assert_eq!(other.get(id), None);
+        assert_eq!(other.remove(id), None);
+        assert!(other.insert_after(id, 5).is_err());
+        let copy=list.clone();
+        assert_eq!(copy.get(id), None);
+        assert_eq!(copy.front_id().and_then(|id| copy.get(id)), Some(&0));
+        assert_eq!(list.remove(id), Some(0));
+        assert_list(&other, &[10, 11, 12]);
+    }
+
+    #[test]
+    fn it_iterates_from_both_ends() {
+        let mut list:IndexList<i32>=(1..5).collect();
+        for val in &mut list {
+            *val*=10;
+        }
+        let mut iter_mut=list.iter_mut();
+        *iter_mut.next_back().unwrap()+=1;
+        assert_eq!(iter_mut.len(), 3);
+        assert_list(&list, &[10, 20, 30, 41]);
+        assert_eq!(list.clone().into_iter().rev().collect::<Vec<i32>>(), vec![41, 30, 20, 10]);
+        assert_eq!(format!("{:?}", list), "[10, 20, 30, 41]");
+        assert_eq!(list, list.clone());
+    }
+
+    extern crate test;
+    use self::test::{Bencher, black_box};
+
+    const BENCH_LENGTH: u32=10000;
+
+    #[bench]
+    fn bench_index_list_insert_back(b: &mut Bencher) {
+        b.iter(|| {
+            let mut list=IndexList::new();
+            for i in 0..BENCH_LENGTH {
+                list.insert_back(i);
+            }
+            list
+        });
+    }
+
+    #[bench]
+    fn bench_linked_list_insert_back(b: &mut Bencher) {
+        b.iter(|| {
+            let mut list=LinkedList::new();
+            for i in 0..BENCH_LENGTH {
+                list.insert_back(i);
+            }
+            list
+        });
+    }
+
+    #[bench]
+    fn bench_index_list_pop_front(b: &mut Bencher) {
+        let mut list:IndexList<u32>=(0..BENCH_LENGTH).collect();
+        b.iter(|| {
+            let value=list.pop_front().unwrap();
+            list.insert_back(value);
+        });
+    }
+
+    #[bench]
+    fn bench_linked_list_pop_front(b: &mut Bencher) {
+        let mut list:LinkedList<u32>=(0..BENCH_LENGTH).collect();
+        b.iter(|| {
+            let value=list.pop_front().unwrap();
+            list.insert_back(value);
+        });
+    }
+
+    #[bench]
+    fn bench_index_list_iterate(b: &mut Bencher) {
+        let list:IndexList<u32>=(0..BENCH_LENGTH).collect();
+        b.iter(|| black_box(list.iter().sum::<u32>()));
+    }
+
+    #[bench]
+    fn bench_linked_list_iterate(b: &mut Bencher) {
+        let list:LinkedList<u32>=(0..BENCH_LENGTH).collect();
+        b.iter(|| black_box(list.iter().sum::<u32>()));
+    }
+}
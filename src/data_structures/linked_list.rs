@@ -1,8 +1,21 @@
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 
 type OptionLink<T> = Option<Rc<RefCell<Node<T>>>>;
 
+static NEXT_LIST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Return an id no list has had before
+fn next_list_id() -> u64 {
+    NEXT_LIST_ID.fetch_add(1, AtomicOrdering::Relaxed)
+}
+
 #[derive(Clone, Debug)]
 struct Node<T>{
     pub prev:OptionLink<T>,
@@ -10,31 +23,289 @@ struct Node<T>{
     pub data:T
 }
 
-pub struct ListIterator<T> {
-    next_node:OptionLink<T>
+/// Iterator over clones of the elements that also marks a position for insert_after and insert_before
+///
+/// It borrows its list, so no element can be handed out mutably while it reads them. To insert at the
+/// element it is at, take its position, which outlives the borrow.
+/// ```compile_fail,E0502
+/// use mldsa_rust::data_structures::linked_list::LinkedList;
+/// let mut list=LinkedList::new();
+/// list.insert_back(String::from("a"));
+/// let mut iter=list.cloned_iter();
+/// for value in list.iter_mut() {
+///     let copy=iter.next();
+///     value.push_str("b");
+/// }
+/// ```
+pub struct ListIterator<'a, T: 'a> {
+    next_node:Option<Weak<RefCell<Node<T>>>>,
+    list_id:u64,
+    list:PhantomData<&'a LinkedList<T>>
+}
+
+impl<'a, T> ListIterator<'a, T> {
+    /// Return the position of the element the next call to next yields
+    pub fn position(&self) -> ListPosition<T> {
+        ListPosition { node: self.next_node.clone(), list_id: self.list_id }
+    }
 }
 
-impl<T> Iterator for ListIterator<T> where T : Clone {
+impl<'a, T> Iterator for ListIterator<'a, T> where T : Clone {
     type Item=T;
     fn next(&mut self) -> Option<Self::Item>{
-        self.next_node.take().and_then(|next_node| {
-            self.next_node=next_node.borrow().next.clone();
-            Some(next_node.borrow().data.clone()) }).or(None)
+        let next_node=self.next_node.take()?.upgrade()?;
+        let node=next_node.borrow();
+        self.next_node=node.next.as_ref().map(Rc::downgrade);
+        Some(node.data.clone())
+    }
+}
+
+/// Element of a list that insert_after and insert_before insert next to, taken from a ListIterator
+///
+/// It only holds a weak reference and never reads the element, so it may be kept while the list changes.
+/// It also records the id of its list, and insert_after and insert_before refuse it on any other list
+/// or once its node is gone.
+pub struct ListPosition<T> {
+    node:Option<Weak<RefCell<Node<T>>>>,
+    list_id:u64
+}
+
+/// Iterator over references to the elements, from either end
+pub struct Iter<'a, T: 'a> {
+    next_node:Option<&'a RefCell<Node<T>>>,
+    back_node:Option<&'a RefCell<Node<T>>>,
+    remaining:usize
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item=&'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.next_node.map(|cell| {
+            // The list stays borrowed for 'a and every mutation needs it mutably, so the node can neither change nor be freed.
+            // No other list can reach the node either, as nodes are only linked through their own list.
+            let node=unsafe { &*cell.as_ptr() };
+            self.next_node=node.next.as_deref();
+            self.remaining-=1;
+            &node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back_node.map(|cell| {
+            let node=unsafe { &*cell.as_ptr() };
+            self.back_node=node.prev.as_deref();
+            self.remaining-=1;
+            &node.data
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// Iterator over mutable references to the elements, from either end
+pub struct IterMut<'a, T: 'a> {
+    next_node:Option<&'a RefCell<Node<T>>>,
+    back_node:Option<&'a RefCell<Node<T>>>,
+    remaining:usize
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item=&'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.next_node.map(|cell| {
+            // The list stays mutably borrowed for 'a and the count stops both ends before they meet,
+            // so every node is handed out once and the references never alias. A ListIterator borrows
+            // the list too, so none can read a node meanwhile, and a ListPosition never reads it.
+            let node=cell.as_ptr();
+            self.remaining-=1;
+            unsafe {
+                self.next_node=(*node).next.as_deref();
+                &mut (*node).data
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back_node.map(|cell| {
+            let node=cell.as_ptr();
+            self.remaining-=1;
+            unsafe {
+                self.back_node=(*node).prev.as_deref();
+                &mut (*node).data
+            }
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+/// Iterator that moves the elements out of the list, from either end
+pub struct IntoIter<T> {
+    list:LinkedList<T>
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item=T;
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+/// Mutable position in a list that can walk both ways and edit around itself
+///
+/// Besides the elements there is one extra "ghost" position between the back and the front of the list,
+/// where the cursor has no current element. Moving past either end lands on it, and moving on from it wraps around.
+/// While it lives no ListIterator can read the list, so the elements it hands out are not read behind its back.
+/// ```compile_fail,E0502
+/// use mldsa_rust::data_structures::linked_list::LinkedList;
+/// let mut list=LinkedList::new();
+/// list.insert_back(String::from("a"));
+/// let mut iter=list.cloned_iter();
+/// let mut cursor=list.cursor_front_mut();
+/// let value=cursor.current().unwrap();
+/// let copy=iter.next();
+/// value.push_str("b");
+/// ```
+pub struct CursorMut<'a, T: 'a> {
+    list:&'a mut LinkedList<T>,
+    current:OptionLink<T>,
+    index:usize
+}
+
 pub struct LinkedList<T> {
     head: OptionLink<T>,
     tail: OptionLink<T>,
-    size: usize
+    size: usize,
+    /// Changed whenever nodes leave for another list, so the ListPositions of them stop matching
+    id: u64
 }
 
-impl<T> LinkedList<T> where T: Clone {
+impl<T> IntoIterator for LinkedList<T> {
+    type Item=T;
+    type IntoIter=IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item=&'a T;
+    type IntoIter=Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item=&'a mut T;
+    type IntoIter=IterMut<'a, T>;
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter:I) -> LinkedList<T> {
+        let mut list=LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter:I) {
+        for data in iter {
+            self.insert_back(data);
+        }
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> LinkedList<T> {
+        LinkedList::new()
+    }
+}
+
+impl<T> Clone for LinkedList<T> where T : Clone {
+    fn clone(&self) -> LinkedList<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> fmt::Debug for LinkedList<T> where T : fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for LinkedList<T> where T : PartialEq {
+    fn eq(&self, other:&LinkedList<T>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for LinkedList<T> where T : Eq {}
+
+impl<T> Hash for LinkedList<T> where T : Hash {
+    fn hash<H: Hasher>(&self, state:&mut H) {
+        self.size.hash(state);
+        for data in self.iter() {
+            data.hash(state);
+        }
+    }
+}
+
+/// Unlinks the nodes one at a time: every node is referenced by both neighbours, so leaving it to the
+/// reference counts would leak the cycles, and freeing a long chain recursively would overflow the stack
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<T> LinkedList<T> {
     pub fn new() -> LinkedList<T> {
         LinkedList{
             head:None,
             tail:None,
-            size:0
+            size:0,
+            id:next_list_id()
         }
     }
 
@@ -42,15 +313,51 @@ impl<T> LinkedList<T> where T: Clone {
         self.size
     }
 
-    pub fn iter(&self) -> ListIterator<T> {
-        ListIterator { next_node: self.head.clone() }
+    /// Iterate over references to the elements, from the front or with rev() from the back
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::linked_list::LinkedList;
+    /// let mut list=LinkedList::new();
+    /// list.insert_back(String::from("a"));
+    /// list.insert_back(String::from("b"));
+    /// assert_eq!(list.iter().map(|s| s.as_str()).collect::<Vec<&str>>(), vec!["a", "b"]);
+    /// assert_eq!(list.iter().rev().map(|s| s.as_str()).collect::<Vec<&str>>(), vec!["b", "a"]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next_node: self.head.as_deref(), back_node: self.tail.as_deref(), remaining: self.size }
+    }
+
+    /// Iterate over mutable references to the elements
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { next_node: self.head.as_deref(), back_node: self.tail.as_deref(), remaining: self.size }
+    }
+
+    /// Iterate over clones of the elements, starting at the front
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::linked_list::LinkedList;
+    /// let mut list=LinkedList::new();
+    /// list.insert_back(1);
+    /// list.insert_back(3);
+    /// let mut iter=list.cloned_iter();
+    /// assert_eq!(iter.next(), Some(1));
+    /// let position=iter.position();
+    /// list.insert_before(&position, 2).unwrap();
+    /// assert_eq!(list.cloned_iter().collect::<Vec<i32>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn cloned_iter(&self) -> ListIterator<'_, T> {
+        ListIterator { next_node: self.head.as_ref().map(Rc::downgrade), list_id: self.id, list: PhantomData }
     }
 
     pub fn empty(&self) -> bool {
         self.head.as_ref().and_then(|_| Some(false)).unwrap_or(true)
     }
 
-    pub fn is_empty(&self) -> bool { self.empty() }
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
 
     pub fn count_front(&self) -> usize {
         let mut node=self.head.clone();
@@ -72,10 +379,10 @@ impl<T> LinkedList<T> where T: Clone {
         return count;
     }
 
-    pub fn insert_back(&mut self, data:&T) {
+    pub fn insert_back(&mut self, data:T) {
         if self.is_empty() {
             self.tail=Some( Rc::new(RefCell::new(Node {
-                data: data.clone(),
+                data,
                 next: None,
                 prev: None
             }) ) );
@@ -83,7 +390,7 @@ impl<T> LinkedList<T> where T: Clone {
         } else {
             let mut tail = self.tail.take();
             self.tail = Some( Rc::new(RefCell::new(Node {
-                data: data.clone(),
+                data,
                 next: None,
                 prev: tail.clone(),
             }) ) );
@@ -92,10 +399,10 @@ impl<T> LinkedList<T> where T: Clone {
         self.size+=1;
     }
 
-    pub fn insert_front(&mut self, data:&T) {
+    pub fn insert_front(&mut self, data:T) {
         if self.is_empty() {
             self.head=Some( Rc::new(RefCell::new(Node {
-                data: data.clone(),
+                data,
                 next: None,
                 prev: None
             }) ) );
@@ -103,7 +410,7 @@ impl<T> LinkedList<T> where T: Clone {
         } else {
             let mut head = self.head.take();
             self.head = Some( Rc::new(RefCell::new(Node {
-                data: data.clone(),
+                data,
                 next: head.clone(),
                 prev: None
             }) ) );
@@ -112,15 +419,12 @@ impl<T> LinkedList<T> where T: Clone {
         self.size+=1;
     }
 
-    pub fn insert_after(&mut self, iterator:&ListIterator<T>, data:&T) -> Result<(), &str> {
-        if iterator.next_node.is_none() {
-            return Err("fail");
-        }
-        let before=iterator.next_node.clone().unwrap();
+    pub fn insert_after(&mut self, position:&ListPosition<T>, data:T) -> Result<(), &'static str> {
+        let before=self.position_node(position)?;
         let node= Some(Rc::new(RefCell::new(Node {
             prev:Some(before.clone()),
             next:before.borrow().next.clone(),
-            data:data.clone()
+            data
         })));
 
         let next=(*before).borrow_mut().next.clone();
@@ -135,15 +439,12 @@ impl<T> LinkedList<T> where T: Clone {
         self.size+=1;
         Ok(())
     }
-    pub fn insert_before(&mut self, iterator:&ListIterator<T>, data:&T) -> Result<(), &str> {
-        if iterator.next_node.is_none() {
-            return Err("fail");
-        }
-        let after=iterator.next_node.clone().unwrap();
+    pub fn insert_before(&mut self, position:&ListPosition<T>, data:T) -> Result<(), &'static str> {
+        let after=self.position_node(position)?;
         let node=Some(Rc::new(RefCell::new(Node {
             prev:after.borrow().prev.clone(),
             next:Some(after.clone()),
-            data:data.clone()
+            data
         })));
 
         let prev=(*after).borrow_mut().prev.clone();
@@ -158,42 +459,414 @@ impl<T> LinkedList<T> where T: Clone {
         Ok(())
     }
 
+    /// Return the node of the position, provided it was taken from this list
+    fn position_node(&self, position:&ListPosition<T>) -> Result<Rc<RefCell<Node<T>>>, &'static str> {
+        if position.list_id != self.id {
+            return Err("Position belongs to another list");
+        }
+        position.node.as_ref().and_then(Weak::upgrade).ok_or("fail")
+    }
+
+    /// Return a cursor at the front element, or at the ghost position when the list is empty
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::linked_list::LinkedList;
+    /// let mut list=LinkedList::new();
+    /// for i in 0..5 {
+    ///     list.insert_back(i);
+    /// }
+    /// let mut cursor=list.cursor_front_mut();
+    /// cursor.move_next();
+    /// assert_eq!(cursor.remove_current(), Some(1));
+    /// cursor.insert_before(10);
+    /// let back=cursor.split_after();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), vec![0, 10, 2]);
+    /// assert_eq!(back.iter().cloned().collect::<Vec<i32>>(), vec![3, 4]);
+    /// ```
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let current=self.head.clone();
+        let index=if current.is_some() { 0 } else { self.size };
+        CursorMut { list: self, current, index }
+    }
+
+    /// Return a cursor at the back element, or at the ghost position when the list is empty
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let current=self.tail.clone();
+        let index=self.size.saturating_sub(1);
+        CursorMut { list: self, current, index }
+    }
+
+    /// Link the chain from first to last, holding count nodes, between prev and next, where None stands for the ends of the list
+    fn link_chain(&mut self, prev:OptionLink<T>, next:OptionLink<T>, first:Rc<RefCell<Node<T>>>, last:Rc<RefCell<Node<T>>>, count:usize) {
+        match prev {
+            Some(ref prev) => prev.borrow_mut().next=Some(first.clone()),
+            None => self.head=Some(first.clone())
+        }
+        match next {
+            Some(ref next) => next.borrow_mut().prev=Some(last.clone()),
+            None => self.tail=Some(last.clone())
+        }
+        first.borrow_mut().prev=prev;
+        last.borrow_mut().next=next;
+        self.size+=count;
+    }
+
     pub fn pop_front(&mut self) -> Option<T> {
-        if self.size == 1 {
-            let ret_val=self.head.as_ref().and_then(|link| Some(link.borrow().data.clone())).or(None);
-            self.head=None;
-            self.tail=None;
-            self.size=0;
-            return ret_val;
-        }
-        self.head.take().and_then( |value| {
-            self.head =value.borrow().next.clone();
-            self.size-=1;
-            if self.size==1 {
-                self.tail=self.head.clone();
+        self.unlink_front().map(into_data)
+    }
+
+    /// Detach the front node without freeing it, so it can be linked elsewhere
+    fn unlink_front(&mut self) -> OptionLink<T> {
+        self.head.take().inspect(|head| {
+            match head.borrow_mut().next.take() {
+                Some(next) => {
+                    next.borrow_mut().prev=None;
+                    self.head=Some(next);
+                },
+                None => self.tail=None
             }
-            return Some(value.borrow().data.clone())
-        } ).or(None)
+            self.size-=1;
+        })
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
-        if self.size == 1 {
-            let ret_val=self.head.as_ref().and_then(|link| Some(link.borrow().data.clone())).or(None);
-            self.head=None;
-            self.tail=None;
+        self.tail.take().map(|tail| {
+            match tail.borrow_mut().prev.take() {
+                Some(prev) => {
+                    prev.borrow_mut().next=None;
+                    self.tail=Some(prev);
+                },
+                None => self.head=None
+            }
             self.size-=1;
-            return ret_val;
+            into_data(tail)
+        })
+    }
+
+    /// Reverse the order of the elements by swapping the links of every node
+    pub fn reverse(&mut self) {
+        let mut current=self.head.clone();
+        while let Some(node)=current {
+            let mut node=node.borrow_mut();
+            let node=&mut *node;
+            ::std::mem::swap(&mut node.prev, &mut node.next);
+            current=node.prev.clone();
         }
-        self.tail.take().and_then( |value| {
-            self.tail=value.borrow().prev.clone();
-            self.size-=1;
-            if self.size==1 {
-                self.head=self.tail.clone();
+        ::std::mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    /// Keep only the elements for which keep returns true
+    pub fn retain<F>(&mut self, mut keep:F) where F : FnMut(&T) -> bool {
+        let mut cursor=self.cursor_front_mut();
+        while let Some(data)=cursor.current() {
+            if keep(data) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
+
+    /// Remove every element that same_bucket matches with the element kept before it
+    pub fn dedup_by<F>(&mut self, mut same_bucket:F) where F : FnMut(&T, &T) -> bool {
+        let mut current=self.head.clone();
+        while let Some(node)=current {
+            let next=node.borrow().next.clone();
+            match next {
+                Some(next) if same_bucket(&next.borrow().data, &node.borrow().data) => {
+                    let after=next.borrow_mut().next.take();
+                    next.borrow_mut().prev=None;
+                    match after {
+                        Some(ref after) => after.borrow_mut().prev=Some(node.clone()),
+                        None => self.tail=Some(node.clone())
+                    }
+                    node.borrow_mut().next=after;
+                    self.size-=1;
+                    into_data(next);
+                    current=Some(node);
+                },
+                next => current=next
+            }
+        }
+    }
+
+    /// Merge the sorted other list into this sorted list, moving its nodes in without reallocating them
+    ///
+    /// Equal elements of this list stay ahead of those of other.
+    pub fn merge_by<F>(&mut self, other:LinkedList<T>, mut compare:F) where F : FnMut(&T, &T) -> Ordering {
+        let mut other=other;
+        let mut current=self.head.clone();
+        while let Some(node)=other.unlink_front() {
+            while let Some(candidate)=current.clone() {
+                if compare(&candidate.borrow().data, &node.borrow().data) == Ordering::Greater {
+                    break;
+                }
+                current=candidate.borrow().next.clone();
+            }
+            let prev=match current {
+                Some(ref current) => current.borrow().prev.clone(),
+                None => self.tail.clone()
+            };
+            self.link_chain(prev, current.clone(), node.clone(), node, 1);
+        }
+    }
+
+    /// Stable sort that relinks the nodes instead of moving or cloning the elements
+    ///
+    /// Bottom-up merge sort: bins[i] holds a sorted run of 2^i nodes, and every new node is carried up
+    /// through the full bins like a binary counter, so only O(log n) bins are kept besides the nodes.
+    pub fn sort_by<F>(&mut self, mut compare:F) where F : FnMut(&T, &T) -> Ordering {
+        let mut bins:Vec<LinkedList<T>>=Vec::new();
+        while let Some(node)=self.unlink_front() {
+            let mut carry=LinkedList::new();
+            carry.link_chain(None, None, node.clone(), node, 1);
+            let mut i=0;
+            while i < bins.len() && !bins[i].is_empty() {
+                let mut bin=::std::mem::take(&mut bins[i]);
+                bin.merge_by(carry, &mut compare);
+                carry=bin;
+                i+=1;
+            }
+            if i == bins.len() {
+                bins.push(carry);
+            } else {
+                bins[i]=carry;
+            }
+        }
+        // Higher bins hold earlier elements, so merging them in front keeps the sort stable
+        for bin in bins {
+            let mut bin=bin;
+            bin.merge_by(::std::mem::take(self), &mut compare);
+            *self=bin;
+        }
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Return the index of the current element, or None at the ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.current.as_ref().map(|_| self.index)
+    }
+
+    /// Move to the next element, or from the back element to the ghost position and from there to the front
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            Some(node) => {
+                self.current=node.borrow().next.clone();
+                self.index+=1;
+            },
+            None => {
+                self.current=self.list.head.clone();
+                self.index=0;
+            }
+        }
+    }
+
+    /// Move to the previous element, or from the front element to the ghost position and from there to the back
+    pub fn move_prev(&mut self) {
+        match self.current.take() {
+            Some(node) => {
+                self.current=node.borrow().prev.clone();
+                self.index=if self.current.is_some() { self.index-1 } else { self.list.size };
+            },
+            None => {
+                self.current=self.list.tail.clone();
+                self.index=self.list.size.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Return the current element, or None at the ghost position
+    pub fn current(&mut self) -> Option<&mut T> {
+        // The cursor borrows the list mutably and the reference borrows the cursor, so while it lives no
+        // ListIterator can read the node and no ListPosition can be inserted at, which is all that reaches it
+        self.current.as_ref().map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    /// Return the element a move_next would land on
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next=match self.current {
+            Some(ref node) => node.borrow().next.clone(),
+            None => self.list.head.clone()
+        };
+        // Sound for the same reasons as current, the list keeping the node alive after the clone is dropped
+        next.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    /// Return the element a move_prev would land on
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev=match self.current {
+            Some(ref node) => node.borrow().prev.clone(),
+            None => self.list.tail.clone()
+        };
+        // Sound for the same reasons as current
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).data })
+    }
+
+    /// Remove the current element and move to the next one, or do nothing at the ghost position
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node=self.current.take()?;
+        let (prev, next)={
+            let mut node=node.borrow_mut();
+            (node.prev.take(), node.next.take())
+        };
+        match prev {
+            Some(ref prev) => prev.borrow_mut().next=next.clone(),
+            None => self.list.head=next.clone()
+        }
+        match next {
+            Some(ref next) => next.borrow_mut().prev=prev.clone(),
+            None => self.list.tail=prev.clone()
+        }
+        self.list.size-=1;
+        self.current=next;
+        Some(into_data(node))
+    }
+
+    /// Insert an element after the current one, or at the front at the ghost position
+    pub fn insert_after(&mut self, data:T) {
+        let mut list=LinkedList::new();
+        list.insert_back(data);
+        self.splice_after(list);
+    }
+
+    /// Insert an element before the current one, or at the back at the ghost position
+    pub fn insert_before(&mut self, data:T) {
+        let mut list=LinkedList::new();
+        list.insert_back(data);
+        self.splice_before(list);
+    }
+
+    /// Move every element of other in after the current one, or at the front at the ghost position, in O(1)
+    pub fn splice_after(&mut self, other:LinkedList<T>) {
+        let mut other=other;
+        let (first, last)=match (other.head.take(), other.tail.take()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return
+        };
+        let count=other.size;
+        other.size=0;
+        let next=match self.current {
+            Some(ref node) => node.borrow().next.clone(),
+            None => self.list.head.clone()
+        };
+        self.list.link_chain(self.current.clone(), next, first, last, count);
+        if self.current.is_none() {
+            self.index+=count;
+        }
+    }
+
+    /// Move every element of other in before the current one, or at the back at the ghost position, in O(1)
+    pub fn splice_before(&mut self, other:LinkedList<T>) {
+        let mut other=other;
+        let (first, last)=match (other.head.take(), other.tail.take()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return
+        };
+        let count=other.size;
+        other.size=0;
+        let prev=match self.current {
+            Some(ref node) => node.borrow().prev.clone(),
+            None => self.list.tail.clone()
+        };
+        self.list.link_chain(prev, self.current.clone(), first, last, count);
+        self.index+=count;
+    }
+
+    /// Cut the list after the current element and return the back part, or the whole list at the ghost position
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let node=match self.current {
+            Some(ref node) => node.clone(),
+            None => {
+                self.index=0;
+                return ::std::mem::take(self.list);
             }
-            return Some(value.borrow().data.clone())
-        } ).or(None)
+        };
+        let rest=node.borrow_mut().next.take();
+        match rest {
+            Some(rest) => {
+                rest.borrow_mut().prev=None;
+                let size=self.list.size-self.index-1;
+                self.list.size=self.index+1;
+                self.list.id=next_list_id();
+                LinkedList {
+                    head: Some(rest),
+                    tail: self.list.tail.replace(node),
+                    size,
+                    id: next_list_id()
+                }
+            },
+            None => LinkedList::new()
+        }
     }
 
+    /// Cut the list before the current element and return the front part, or the whole list at the ghost position
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        let node=match self.current {
+            Some(ref node) => node.clone(),
+            None => {
+                self.index=0;
+                return ::std::mem::take(self.list);
+            }
+        };
+        let front=node.borrow_mut().prev.take();
+        match front {
+            Some(front) => {
+                front.borrow_mut().next=None;
+                let size=self.index;
+                self.list.size-=size;
+                self.index=0;
+                self.list.id=next_list_id();
+                LinkedList {
+                    head: self.list.head.replace(node),
+                    tail: Some(front),
+                    size,
+                    id: next_list_id()
+                }
+            },
+            None => LinkedList::new()
+        }
+    }
+}
+
+/// Move the data out of a node that has been unlinked from both its neighbours and the list
+fn into_data<T>(node:Rc<RefCell<Node<T>>>) -> T {
+    match Rc::try_unwrap(node) {
+        Ok(node) => node.into_inner().data,
+        Err(_) => panic!("An unlinked node is still shared")
+    }
+}
+
+impl<T> LinkedList<T> where T: Ord {
+    /// Sort the elements in ascending order, see sort_by
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::linked_list::LinkedList;
+    /// let mut list:LinkedList<i32>=vec![3, 1, 2, 3, 1].into_iter().collect();
+    /// list.sort();
+    /// list.dedup();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn sort(&mut self) {
+        self.sort_by(|a, b| a.cmp(b));
+    }
+
+    /// Merge the sorted other list into this sorted list, see merge_by
+    pub fn merge(&mut self, other:LinkedList<T>) {
+        self.merge_by(other, |a, b| a.cmp(b));
+    }
+}
+
+impl<T> LinkedList<T> where T: PartialEq {
+    /// Remove consecutive repeated elements
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b);
+    }
+}
+
+impl<T> LinkedList<T> where T: Clone {
     pub fn front(&self) -> Result<T, &str> {
         self.head.as_ref().and_then(|head| Some(head.borrow().data.clone()) ).ok_or("No element")
     }
@@ -206,106 +879,109 @@ impl<T> LinkedList<T> where T: Clone {
 #[cfg(test)]
 mod tests{
     use super::LinkedList;
+    extern crate rand;
+    use self::rand::{Rng, SeedableRng};
+    use self::rand::rngs::StdRng;
     #[test]
     fn it_inserts_back_once() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
+        list.insert_back(1);
         assert_eq!(list.back().unwrap(),1);
     }
 
     #[test]
     fn it_inserts_back_twice() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
+        list.insert_back(1);
+        list.insert_back(2);
         assert_eq!(list.back().unwrap(),2);
     }
 
     #[test]
     fn it_inserts_back_three_times() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
         assert_eq!(list.back().unwrap(),3);
     }
 
     #[test]
     fn it_inserts_front_to_empty_list() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
+        list.insert_front(1);
         assert_eq!(list.front().unwrap(),1);
     }
 
     #[test]
     fn it_inserts_front_twice_to_empty_list() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.front().unwrap(),2);
     }
 
     #[test]
     fn it_inserts_front_to_one_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_front(13);
         assert_eq!(list.front().unwrap(),13);
     }
 
     #[test]
     fn it_inserts_front_twice_to_one_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.front().unwrap(),2);
     }
 
     #[test]
     fn it_inserts_front_to_two_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(13);
         assert_eq!(list.front().unwrap(),13);
     }
 
     #[test]
     fn it_inserts_front_twice_to_two_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.front().unwrap(),2);
     }
 
     #[test]
     fn it_inserts_front_to_three_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(13);
         assert_eq!(list.front().unwrap(),13);
     }
 
     #[test]
     fn it_inserts_front_twice_to_three_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.front().unwrap(),2);
     }
 
     #[test]
     fn it_inserts_back_once_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
+        list.insert_back(1);
         assert_eq!(list.pop_front().unwrap(), 1);
         assert_eq!(list.pop_front(), None);
     }
@@ -313,8 +989,8 @@ mod tests{
     #[test]
     fn it_inserts_back_twice_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
+        list.insert_back(1);
+        list.insert_back(2);
         assert_eq!(list.pop_front().unwrap(),1);
         list.pop_front();
         assert_eq!(list.pop_front(), None);
@@ -323,9 +999,9 @@ mod tests{
     #[test]
     fn it_inserts_back_three_times_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
         assert_eq!(list.pop_front().unwrap(),1);
         list.pop_front();
         list.pop_front();
@@ -335,7 +1011,7 @@ mod tests{
     #[test]
     fn it_inserts_front_to_empty_list_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
+        list.insert_front(1);
         assert_eq!(list.pop_front().unwrap(),1);
         assert_eq!(list.pop_front(), None);
     }
@@ -343,8 +1019,8 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_empty_list_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_front().unwrap(),2);
         list.pop_front();
         assert_eq!(list.pop_front(), None);
@@ -353,8 +1029,8 @@ mod tests{
     #[test]
     fn it_inserts_front_to_one_length_list_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_front(13);
         assert_eq!(list.pop_front().unwrap(),13);
         list.pop_front();
         assert_eq!(list.pop_front(), None);
@@ -363,9 +1039,9 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_one_length_list_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_front().unwrap(),2);
         list.pop_front();
         list.pop_front();
@@ -375,9 +1051,9 @@ mod tests{
     #[test]
     fn it_inserts_front_to_two_length_list_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(13);
         assert_eq!(list.pop_front().unwrap(),13);
         list.pop_front();
         list.pop_front();
@@ -387,10 +1063,10 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_two_length_list_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_front().unwrap(),2);
         list.pop_front();
         list.pop_front();
@@ -401,10 +1077,10 @@ mod tests{
     #[test]
     fn it_inserts_front_to_three_length_list_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(13);
         assert_eq!(list.pop_front().unwrap(),13);
         list.pop_front();
         list.pop_front();
@@ -415,11 +1091,11 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_three_length_list_and_pop_front() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_front().unwrap(),2);
         list.pop_front();
         list.pop_front();
@@ -431,7 +1107,7 @@ mod tests{
     #[test]
     fn it_inserts_back_once_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
+        list.insert_back(1);
         assert_eq!(list.pop_back().unwrap(), 1);
         assert_eq!(list.pop_back(), None);
     }
@@ -439,8 +1115,8 @@ mod tests{
     #[test]
     fn it_inserts_back_twice_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
+        list.insert_back(1);
+        list.insert_back(2);
         assert_eq!(list.pop_back().unwrap(),2);
         list.pop_back();
         assert_eq!(list.pop_back(), None);
@@ -449,9 +1125,9 @@ mod tests{
     #[test]
     fn it_inserts_back_three_times_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
         assert_eq!(list.pop_back().unwrap(),3);
         list.pop_back();
         list.pop_back();
@@ -461,7 +1137,7 @@ mod tests{
     #[test]
     fn it_inserts_front_to_empty_list_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
+        list.insert_front(1);
         assert_eq!(list.pop_back().unwrap(),1);
         assert_eq!(list.pop_back(), None);
     }
@@ -469,8 +1145,8 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_empty_list_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_back().unwrap(),1);
         list.pop_back();
         assert_eq!(list.pop_back(), None);
@@ -479,8 +1155,8 @@ mod tests{
     #[test]
     fn it_inserts_front_to_one_length_list_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_front(13);
         assert_eq!(list.pop_back().unwrap(),1);
         list.pop_back();
         assert_eq!(list.pop_back(), None);
@@ -489,9 +1165,9 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_one_length_list_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_back().unwrap(),1);
         list.pop_back();
         list.pop_back();
@@ -501,9 +1177,9 @@ mod tests{
     #[test]
     fn it_inserts_front_to_two_length_list_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(13);
         assert_eq!(list.pop_back().unwrap(),2);
         list.pop_back();
         list.pop_back();
@@ -513,10 +1189,10 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_two_length_list_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_back().unwrap(),2);
         list.pop_back();
         list.pop_back();
@@ -527,10 +1203,10 @@ mod tests{
     #[test]
     fn it_inserts_front_to_three_length_list_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(13);
         assert_eq!(list.pop_back().unwrap(),3);
         list.pop_back();
         list.pop_back();
@@ -541,11 +1217,11 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_three_length_list_and_pop_back() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_back().unwrap(),3);
         list.pop_back();
         list.pop_back();
@@ -557,11 +1233,11 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_three_length_list_and_pop_back_and_forth() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.pop_back().unwrap(),3);
         assert_eq!(list.pop_front().unwrap(),2);
         assert_eq!(list.pop_back().unwrap(),2);
@@ -574,7 +1250,7 @@ mod tests{
     #[test]
     fn it_inserts_back_once_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
+        list.insert_back(1);
         assert_eq!(list.back().unwrap(),1);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),1);
@@ -583,8 +1259,8 @@ mod tests{
     #[test]
     fn it_inserts_back_twice_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
+        list.insert_back(1);
+        list.insert_back(2);
         assert_eq!(list.back().unwrap(),2);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),2);
@@ -593,9 +1269,9 @@ mod tests{
     #[test]
     fn it_inserts_back_three_times_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
         assert_eq!(list.back().unwrap(),3);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),3);
@@ -604,7 +1280,7 @@ mod tests{
     #[test]
     fn it_inserts_front_to_empty_list_and_count() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
+        list.insert_front(1);
         assert_eq!(list.front().unwrap(),1);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),1);
@@ -613,8 +1289,8 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_empty_list_and_count() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.front().unwrap(),2);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),2);
@@ -623,8 +1299,8 @@ mod tests{
     #[test]
     fn it_inserts_front_to_one_length_list_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_front(13);
         assert_eq!(list.front().unwrap(),13);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),2);
@@ -633,9 +1309,9 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_one_length_list_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.front().unwrap(),2);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),3);
@@ -644,9 +1320,9 @@ mod tests{
     #[test]
     fn it_inserts_front_to_two_length_list_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(13);
         assert_eq!(list.front().unwrap(),13);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),3);
@@ -655,10 +1331,10 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_two_length_list_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.front().unwrap(),2);
         assert_eq!(list.count_front(),4);
     }
@@ -666,10 +1342,10 @@ mod tests{
     #[test]
     fn it_inserts_front_to_three_length_list_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(13);
         assert_eq!(list.front().unwrap(),13);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),4);
@@ -678,11 +1354,11 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_three_length_list_and_count() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(1);
+        list.insert_front(2);
         assert_eq!(list.front().unwrap(),2);
         assert_eq!(list.count_front(),list.count_back());
         assert_eq!(list.count_front(),5);
@@ -691,20 +1367,20 @@ mod tests{
     #[test]
     fn it_inserts_back_once_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
+        list.insert_back(1);
         for val in list.iter(){
-            assert_eq!(val,1);
+            assert_eq!(*val,1);
         }
     }
 
     #[test]
     fn it_inserts_back_twice_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
+        list.insert_back(1);
+        list.insert_back(2);
         let mut expected_seq=vec![1,2];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -712,12 +1388,12 @@ mod tests{
     #[test]
     fn it_inserts_back_three_times_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
         let mut expected_seq=vec![1,2,3];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -725,10 +1401,10 @@ mod tests{
     #[test]
     fn it_inserts_front_to_empty_list_iteration() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
+        list.insert_front(1);
         let mut expected_seq=vec![1];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -736,11 +1412,11 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_empty_list_iteration() {
         let mut list=LinkedList::new();
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_front(1);
+        list.insert_front(2);
         let mut expected_seq=vec![2,1];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -748,11 +1424,11 @@ mod tests{
     #[test]
     fn it_inserts_front_to_one_length_list_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_front(13);
         let mut expected_seq=vec![13, 1];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -760,12 +1436,12 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_one_length_list_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_front(1);
+        list.insert_front(2);
         let mut expected_seq=vec![2, 1, 1];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -773,12 +1449,12 @@ mod tests{
     #[test]
     fn it_inserts_front_to_two_length_list_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(13);
         let mut expected_seq=vec![13, 1, 2];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -786,13 +1462,13 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_two_length_list_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_front(1);
+        list.insert_front(2);
         let mut expected_seq=vec![2, 1, 1, 2];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -800,13 +1476,13 @@ mod tests{
     #[test]
     fn it_inserts_front_to_three_length_list_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&13);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(13);
         let mut expected_seq=vec![13, 1, 2, 3];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -814,14 +1490,14 @@ mod tests{
     #[test]
     fn it_inserts_front_twice_to_three_length_list_iteration() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        list.insert_front(&1);
-        list.insert_front(&2);
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        list.insert_front(1);
+        list.insert_front(2);
         let mut expected_seq=vec![2, 1, 1, 2, 3];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -829,20 +1505,20 @@ mod tests{
     #[test]
     fn it_insert_after_to_empty_list() {
         let mut list=LinkedList::new();
-        let iter=list.iter();
-        assert!(list.insert_after(&iter, &1).is_err());
+        let position=list.cloned_iter().position();
+        assert!(list.insert_after(&position, 1).is_err());
         assert!(list.is_empty());
     }
 
     #[test]
     fn it_insert_after_to_one_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        let iter=list.iter();
-        list.insert_after(&iter, &2).expect("fail to insert_after");
+        list.insert_back(1);
+        let position=list.cloned_iter().position();
+        list.insert_after(&position, 2).expect("fail to insert_after");
         let mut expected_seq=vec![1, 2];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -850,13 +1526,14 @@ mod tests{
     #[test]
     fn it_insert_after_to_end_of_one_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        let mut iter=list.iter();
+        list.insert_back(1);
+        let mut iter=list.cloned_iter();
         iter.next();
-        list.insert_after(&iter, &2).expect_err("fail to insert_after");
+        let position=iter.position();
+        list.insert_after(&position, 2).expect_err("fail to insert_after");
         let mut expected_seq=vec![1];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -864,12 +1541,12 @@ mod tests{
     #[test]
     fn it_insert_before_to_one_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        let iter=list.iter();
-        list.insert_before(&iter, &2).expect("fail to insert_after");
+        list.insert_back(1);
+        let position=list.cloned_iter().position();
+        list.insert_before(&position, 2).expect("fail to insert_after");
         let mut expected_seq=vec![2, 1];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -877,15 +1554,16 @@ mod tests{
     #[test]
     fn it_insert_before_to_end_of_one_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
+        list.insert_back(1);
 
-        let mut iter=list.iter();
+        let mut iter=list.cloned_iter();
         iter.next();
-        list.insert_before(&iter, &2).expect_err("fail to insert_after");
+        let position=iter.position();
+        list.insert_before(&position, 2).expect_err("fail to insert_after");
 
         let mut expected_seq=vec![1];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -893,13 +1571,13 @@ mod tests{
     #[test]
     fn it_insert_after_to_two_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        let iter = list.iter();
-        list.insert_after(&iter, &3).expect("fail to insert_after");
+        list.insert_back(1);
+        list.insert_back(2);
+        let position=list.cloned_iter().position();
+        list.insert_after(&position, 3).expect("fail to insert_after");
         let mut expected_seq=vec![1, 3, 2];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -907,13 +1585,13 @@ mod tests{
     #[test]
     fn it_insert_before_to_two_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        let iter = list.iter();
-        list.insert_before(&iter, &3).expect("fail to insert_after");
+        list.insert_back(1);
+        list.insert_back(2);
+        let position=list.cloned_iter().position();
+        list.insert_before(&position, 3).expect("fail to insert_after");
         let mut expected_seq=vec![3, 1, 2];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -921,15 +1599,16 @@ mod tests{
     #[test]
     fn it_insert_after_to_middle_of_two_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        let mut iter = list.iter();
+        list.insert_back(1);
+        list.insert_back(2);
+        let mut iter = list.cloned_iter();
         iter.next();
-        list.insert_after(&iter, &3).expect("fail to insert_after");
+        let position=iter.position();
+        list.insert_after(&position, 3).expect("fail to insert_after");
         let mut expected_seq=vec![1, 2, 3];
         assert_eq!(list.size(), expected_seq.len());
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -937,15 +1616,16 @@ mod tests{
     #[test]
     fn it_insert_before_to_middle_of_two_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        let mut iter = list.iter();
+        list.insert_back(1);
+        list.insert_back(2);
+        let mut iter = list.cloned_iter();
         iter.next();
-        list.insert_before(&iter, &3).expect("fail to insert_after");
+        let position=iter.position();
+        list.insert_before(&position, 3).expect("fail to insert_after");
         let mut expected_seq=vec![1, 3, 2];
         assert_eq!(list.size(), expected_seq.len());
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -953,16 +1633,17 @@ mod tests{
     #[test]
     fn it_insert_after_to_end_of_two_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        let mut iter = list.iter();
+        list.insert_back(1);
+        list.insert_back(2);
+        let mut iter = list.cloned_iter();
         iter.next();
         iter.next();
-        list.insert_after(&iter, &3).expect_err("fail to insert_after");
+        let position=iter.position();
+        list.insert_after(&position, 3).expect_err("fail to insert_after");
         let mut expected_seq=vec![1, 2];
         assert_eq!(list.size(), expected_seq.len());
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -970,16 +1651,17 @@ mod tests{
     #[test]
     fn it_insert_before_to_end_of_two_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        let mut iter = list.iter();
+        list.insert_back(1);
+        list.insert_back(2);
+        let mut iter = list.cloned_iter();
         iter.next();
         iter.next();
-        list.insert_before(&iter, &3).expect_err("fail to insert_after");
+        let position=iter.position();
+        list.insert_before(&position, 3).expect_err("fail to insert_after");
         let mut expected_seq=vec![1, 2];
         assert_eq!(list.size(), expected_seq.len());
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
@@ -987,18 +1669,386 @@ mod tests{
     #[test]
     fn it_insert_after_to_three_length_list() {
         let mut list=LinkedList::new();
-        list.insert_back(&1);
-        list.insert_back(&2);
-        list.insert_back(&3);
-        let iter = list.iter();
-        list.insert_after(&iter, &4).expect("fail to insert_after");
+        list.insert_back(1);
+        list.insert_back(2);
+        list.insert_back(3);
+        let position=list.cloned_iter().position();
+        list.insert_after(&position, 4).expect("fail to insert_after");
         let mut expected_seq=vec![1, 4, 2, 3];
         for val in list.iter() {
-            assert_eq!(val, expected_seq.first().unwrap().clone());
+            assert_eq!(*val, expected_seq.first().unwrap().clone());
             expected_seq.remove(0);
         }
     }
 
+    #[test]
+    fn it_iterates_by_reference_without_cloning() {
+        let mut list=LinkedList::new();
+        list.insert_back(String::from("a"));
+        list.insert_back(String::from("b"));
+        let joined:Vec<&str>=list.iter().map(|s| s.as_str()).collect();
+        assert_eq!(joined, vec!["a", "b"]);
+        assert_eq!(list.iter().count(), list.size());
+    }
+
+    #[test]
+    fn it_mutates_through_iter_mut() {
+        let mut list=LinkedList::new();
+        for i in 0..5 {
+            list.insert_back(i);
+        }
+        for val in list.iter_mut() {
+            *val*=10;
+        }
+        assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), vec![0, 10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn it_moves_elements_out_with_into_iter() {
+        let mut list=LinkedList::new();
+        for i in 0..5 {
+            list.insert_front(Box::new(i));
+        }
+        let values:Vec<i32>=list.into_iter().map(|boxed| *boxed).collect();
+        assert_eq!(values, vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn it_holds_payloads_that_cannot_be_cloned() {
+        struct Handle(u32);
+        let mut list=LinkedList::new();
+        list.insert_back(Handle(1));
+        list.insert_back(Handle(2));
+        list.insert_front(Handle(0));
+        assert_eq!(list.pop_back().unwrap().0, 2);
+        assert_eq!(list.pop_front().unwrap().0, 0);
+        assert_eq!(list.pop_front().unwrap().0, 1);
+        assert!(list.pop_front().is_none());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn it_rejects_a_position_whose_node_was_popped() {
+        let mut list=LinkedList::new();
+        list.insert_back(1);
+        list.insert_back(2);
+        let position=list.cloned_iter().position();
+        assert_eq!(list.pop_front(), Some(1));
+        assert!(list.insert_after(&position, 3).is_err());
+        assert!(list.insert_before(&position, 3).is_err());
+        assert_eq!(list.cloned_iter().collect::<Vec<i32>>(), vec![2]);
+    }
+
+    #[test]
+    fn it_rejects_a_position_of_another_list() {
+        let mut a=LinkedList::new();
+        a.insert_back(1);
+        let mut b=LinkedList::new();
+        assert_eq!(b.insert_before(&a.cloned_iter().position(), 99), Err("Position belongs to another list"));
+        assert_eq!(b.insert_after(&a.cloned_iter().position(), 99), Err("Position belongs to another list"));
+        assert!(b.is_empty());
+        assert_eq!(a.iter().cloned().collect::<Vec<i32>>(), vec![1]);
+    }
+
+    #[test]
+    fn it_rejects_a_position_whose_node_was_split_off() {
+        let mut list=LinkedList::new();
+        for i in 0..4 {
+            list.insert_back(i);
+        }
+        let mut iter=list.cloned_iter();
+        iter.next();
+        iter.next();
+        let position=iter.position();
+        let mut back=list.cursor_front_mut();
+        back.move_next();
+        let mut back=back.split_after();
+        assert!(list.insert_after(&position, 9).is_err());
+        assert!(back.insert_after(&position, 9).is_err());
+        assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), vec![0, 1]);
+        assert_eq!(back.iter().cloned().collect::<Vec<i32>>(), vec![2, 3]);
+        let position=list.cloned_iter().position();
+        list.insert_after(&position, 5).unwrap();
+        assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), vec![0, 5, 1]);
+    }
+
+    fn list_of(values:&[i32]) -> LinkedList<i32> {
+        let mut list=LinkedList::new();
+        for value in values {
+            list.insert_back(*value);
+        }
+        list
+    }
+
+    fn assert_list(list:&LinkedList<i32>, expected:&[i32]) {
+        assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), expected.to_vec());
+        assert_eq!(list.size(), expected.len());
+        assert_eq!(list.count_front(), expected.len());
+        assert_eq!(list.count_back(), expected.len());
+    }
+
+    #[test]
+    fn it_moves_a_cursor_both_ways_through_the_ghost_position() {
+        let mut list=list_of(&[1, 2, 3]);
+        let mut cursor=list.cursor_front_mut();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 2));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 3));
+        cursor.move_prev();
+        assert_eq!((cursor.index(), cursor.current()), (Some(2), Some(&mut 3)));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!((cursor.index(), cursor.current()), (Some(0), Some(&mut 1)));
+        *cursor.current().unwrap()=10;
+        assert_list(&list, &[10, 2, 3]);
+    }
+
+    #[test]
+    fn it_removes_the_current_element_and_moves_to_the_next() {
+        let mut list=list_of(&[1, 2, 3]);
+        {
+            let mut cursor=list.cursor_front_mut();
+            cursor.move_next();
+            assert_eq!(cursor.remove_current(), Some(2));
+            assert_eq!(cursor.current(), Some(&mut 3));
+            assert_eq!(cursor.remove_current(), Some(3));
+            assert_eq!(cursor.index(), None);
+            assert_eq!(cursor.remove_current(), None);
+        }
+        assert_list(&list, &[1]);
+        assert_eq!(list.cursor_back_mut().remove_current(), Some(1));
+        assert_list(&list, &[]);
+        assert_eq!(list.back(), Err("No element"));
+    }
+
+    #[test]
+    fn it_inserts_around_the_cursor() {
+        let mut list=list_of(&[2]);
+        {
+            let mut cursor=list.cursor_front_mut();
+            cursor.insert_before(1);
+            cursor.insert_after(3);
+            assert_eq!(cursor.index(), Some(1));
+            cursor.move_next();
+            cursor.move_next();
+            cursor.insert_after(0);
+            cursor.insert_before(4);
+            assert_eq!(cursor.index(), None);
+        }
+        assert_list(&list, &[0, 1, 2, 3, 4]);
+        let mut empty=LinkedList::new();
+        empty.cursor_front_mut().insert_after(7);
+        assert_list(&empty, &[7]);
+    }
+
+    #[test]
+    fn it_keeps_a_position_across_cursor_edits() {
+        let mut list=list_of(&[1, 2]);
+        let position=list.cloned_iter().position();
+        {
+            let mut cursor=list.cursor_front_mut();
+            *cursor.current().unwrap()=10;
+            *cursor.peek_next().unwrap()=20;
+        }
+        list.insert_after(&position, 15).unwrap();
+        assert_list(&list, &[10, 15, 20]);
+    }
+
+    #[test]
+    fn it_splits_around_the_cursor() {
+        let mut list=list_of(&[1, 2, 3, 4, 5]);
+        let (front, back)={
+            let mut cursor=list.cursor_front_mut();
+            cursor.move_next();
+            cursor.move_next();
+            let back=cursor.split_after();
+            let front=cursor.split_before();
+            assert_eq!(cursor.index(), Some(0));
+            assert_eq!(cursor.split_after().size(), 0);
+            (front, back)
+        };
+        assert_list(&front, &[1, 2]);
+        assert_list(&list, &[3]);
+        assert_list(&back, &[4, 5]);
+        let mut list=list_of(&[1, 2]);
+        let mut cursor=list.cursor_back_mut();
+        cursor.move_next();
+        assert_list(&cursor.split_before(), &[1, 2]);
+    }
+
+    #[test]
+    fn it_splices_lists_around_the_cursor() {
+        let mut list=list_of(&[1, 5]);
+        {
+            let mut cursor=list.cursor_front_mut();
+            cursor.splice_after(list_of(&[2, 3, 4]));
+            assert_eq!(cursor.current(), Some(&mut 1));
+            cursor.splice_before(list_of(&[-1, 0]));
+            assert_eq!(cursor.index(), Some(2));
+            cursor.splice_after(LinkedList::new());
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.splice_before(list_of(&[6, 7]));
+            cursor.splice_after(list_of(&[-2]));
+            assert_eq!(cursor.index(), None);
+        }
+        assert_list(&list, &[-2, -1, 0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn it_iterates_in_reverse() {
+        let list=list_of(&[1, 2, 3, 4]);
+        assert_eq!(list.iter().rev().cloned().collect::<Vec<i32>>(), vec![4, 3, 2, 1]);
+        assert_eq!(list.into_iter().rev().collect::<Vec<i32>>(), vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn it_meets_in_the_middle_when_iterating_from_both_ends() {
+        let mut list=list_of(&[1, 2, 3]);
+        let mut iter=list.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next_back(), Some(&2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter_mut=list.iter_mut();
+        *iter_mut.next_back().unwrap()=30;
+        *iter_mut.next().unwrap()=10;
+        assert_eq!(iter_mut.len(), 1);
+        *iter_mut.next().unwrap()=20;
+        assert!(iter_mut.next_back().is_none());
+        assert_list(&list, &[10, 20, 30]);
+
+        let mut into_iter=list.into_iter();
+        assert_eq!(into_iter.len(), 3);
+        assert_eq!(into_iter.next_back(), Some(30));
+        assert_eq!(into_iter.next(), Some(10));
+        assert_eq!(into_iter.len(), 1);
+    }
+
+    #[test]
+    fn it_collects_and_extends() {
+        let mut list:LinkedList<i32>=(1..4).collect();
+        list.extend(vec![4, 5]);
+        assert_list(&list, &[1, 2, 3, 4, 5]);
+        let mut sum=0;
+        for val in &list {
+            sum+=*val;
+        }
+        for val in &mut list {
+            *val+=1;
+        }
+        assert_eq!(sum, 15);
+        assert_list(&list, &[2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn it_clones_compares_and_formats() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        let list=list_of(&[1, 2, 3]);
+        let copy=list.clone();
+        assert_eq!(list, copy);
+        assert_ne!(list, list_of(&[1, 2]));
+        assert_ne!(list, list_of(&[1, 2, 4]));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(LinkedList::<i32>::default(), LinkedList::new());
+        let hash_of=|list:&LinkedList<i32>| {
+            let mut hasher=DefaultHasher::new();
+            list.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash_of(&list), hash_of(&copy));
+    }
+
+    #[test]
+    fn it_drops_every_element() {
+        use std::rc::Rc;
+        let shared=Rc::new(0);
+        let list:LinkedList<Rc<i32>>=(0..100).map(|_| shared.clone()).collect();
+        assert_eq!(Rc::strong_count(&shared), 101);
+        drop(list);
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn it_drops_a_million_element_list() {
+        let list:LinkedList<u32>=(0..1000000).collect();
+        assert_eq!(list.size(), 1000000);
+        drop(list);
+    }
+
+    #[test]
+    fn it_sorts_like_a_vec() {
+        let mut rng=StdRng::from_seed([18u8; 32]);
+        for count in 0..100 {
+            let values:Vec<i32>=(0..count).map(|_| rng.gen::<i32>()%20).collect();
+            let mut list:LinkedList<i32>=values.iter().cloned().collect();
+            let mut sorted=values.clone();
+            sorted.sort();
+            list.sort();
+            assert_list(&list, &sorted);
+        }
+    }
+
+    #[test]
+    fn it_sorts_stably_without_cloning() {
+        let mut list:LinkedList<(u32, Box<u32>)>=(0..50).map(|i| (i%7, Box::new(i))).collect();
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut expected:Vec<(u32, u32)>=(0..50).map(|i| (i%7, i)).collect();
+        expected.sort_by_key(|pair| pair.0);
+        assert_eq!(list.iter().map(|pair| (pair.0, *pair.1)).collect::<Vec<(u32, u32)>>(), expected);
+        assert_eq!(list.count_back(), 50);
+    }
+
+    #[test]
+    fn it_reverses_the_links() {
+        let mut list=list_of(&[1, 2, 3, 4]);
+        list.reverse();
+        assert_list(&list, &[4, 3, 2, 1]);
+        assert_eq!(list.back().unwrap(), 1);
+        let mut empty=list_of(&[]);
+        empty.reverse();
+        assert_list(&empty, &[]);
+    }
+
+    #[test]
+    fn it_retains_and_dedups() {
+        let mut list=list_of(&[1, 1, 2, 3, 3, 3, 4, 1, 1]);
+        list.dedup();
+        assert_list(&list, &[1, 2, 3, 4, 1]);
+        list.retain(|val| val % 2 == 1);
+        assert_list(&list, &[1, 3, 1]);
+        list.retain(|_| false);
+        assert_list(&list, &[]);
+        let mut list=list_of(&[5, 5]);
+        list.dedup();
+        assert_list(&list, &[5]);
+        assert_eq!(list.back().unwrap(), 5);
+    }
+
+    #[test]
+    fn it_merges_sorted_lists() {
+        let mut list=list_of(&[1, 3, 5, 7]);
+        list.merge(list_of(&[0, 3, 4, 8, 9]));
+        assert_list(&list, &[0, 1, 3, 3, 4, 5, 7, 8, 9]);
+        let mut pairs:LinkedList<(u32, char)>=vec![(1, 'a'), (2, 'a')].into_iter().collect();
+        pairs.merge_by(vec![(1, 'b'), (2, 'b')].into_iter().collect(), |a, b| a.0.cmp(&b.0));
+        assert_eq!(pairs.iter().map(|pair| pair.1).collect::<String>(), "abab");
+        let mut empty=list_of(&[]);
+        empty.merge(list_of(&[1, 2]));
+        assert_list(&empty, &[1, 2]);
+    }
+
     extern crate test;
     use self::test::Bencher;
 
@@ -1007,10 +2057,10 @@ mod tests{
         let mut list=LinkedList::new();
         
         for i in 1..10000000 {
-            list.insert_back(&i);
+            list.insert_back(i);
         }
         b.iter(|| {
-            list.insert_back(&1);
+            list.insert_back(1);
         });
     }
 }
\ No newline at end of file
@@ -0,0 +1,360 @@
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use data_structures::queue::{Enqueue, Dequeue};
+
+/// Keeps the producer and consumer positions on separate cache lines so they do not contend
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+/// The sequence tells which lap of the ring the slot is in and whether it holds an element:
+/// equal to the position when it is free for that enqueue, and to position+1 once filled for that dequeue
+struct Slot<T> {
+    sequence:AtomicUsize,
+    data:UnsafeCell<MaybeUninit<T>>
+}
+
+/// Lock-free multi-producer multi-consumer queue over a fixed ring of slots
+///
+/// Producers and consumers claim positions with a compare and swap and then hand the slot over
+/// through its sequence number, so no thread ever waits on a lock. The slots are allocated once
+/// and reused, so no memory is freed while other threads might still read it.
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use mldsa_rust::data_structures::lock_free_queue::LockFreeQueue;
+/// let queue=Arc::new(LockFreeQueue::new(16));
+/// let producers:Vec<_>=(0..4).map(|producer| {
+///     let queue=queue.clone();
+///     thread::spawn(move || queue.enqueue(producer))
+/// }).collect();
+/// for producer in producers {
+///     producer.join().unwrap();
+/// }
+/// let mut received:Vec<i32>=(0..4).map(|_| queue.try_dequeue().unwrap()).collect();
+/// received.sort();
+/// assert_eq!(received, vec![0, 1, 2, 3]);
+/// ```
+pub struct LockFreeQueue<T> {
+    slots:Box<[Slot<T>]>,
+    mask:usize,
+    enqueue_position:CachePadded<AtomicUsize>,
+    dequeue_position:CachePadded<AtomicUsize>
+}
+
+/// Each element is moved in by one thread and out by another, and never shared
+unsafe impl<T: Send> Send for LockFreeQueue<T> {}
+unsafe impl<T: Send> Sync for LockFreeQueue<T> {}
+
+impl<T> LockFreeQueue<T> {
+    /// Construct a queue of capacity rounded up to a power of two, and to at least 2
+    pub fn new(capacity:usize) -> LockFreeQueue<T> {
+        let capacity=capacity.max(2).next_power_of_two();
+        let slots=(0..capacity).map(|position| Slot {
+            sequence: AtomicUsize::new(position),
+            data: UnsafeCell::new(MaybeUninit::uninit())
+        }).collect();
+        LockFreeQueue {
+            slots,
+            mask: capacity-1,
+            enqueue_position: CachePadded(AtomicUsize::new(0)),
+            dequeue_position: CachePadded(AtomicUsize::new(0))
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Return the number of elements, which other threads may change before the caller looks at it
+    pub fn size(&self) -> usize {
+        // The enqueue position never falls behind the dequeue position, and is loaded second so it can only be further ahead
+        let dequeue_position=self.dequeue_position.0.load(Ordering::SeqCst);
+        let enqueue_position=self.enqueue_position.0.load(Ordering::SeqCst);
+        enqueue_position.wrapping_sub(dequeue_position).min(self.capacity())
+    }
+
+    pub fn empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// Add the element at the back, or hand it back when the queue is full
+    pub fn try_enqueue(&self, data:T) -> Result<(), T> {
+        let mut position=self.enqueue_position.0.load(Ordering::Relaxed);
+        loop {
+            let slot=&self.slots[position & self.mask];
+            let sequence=slot.sequence.load(Ordering::Acquire);
+            let lap=sequence.wrapping_sub(position) as isize;
+            if lap == 0 {
+                match self.enqueue_position.0.compare_exchange_weak(position, position.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        // Winning the position makes this thread the only one touching the slot until the sequence moves on
+                        unsafe { (*slot.data.get()).write(data); }
+                        slot.sequence.store(position.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    },
+                    Err(current) => position=current
+                }
+            } else if lap < 0 {
+                // The slot still holds the element from the previous lap
+                return Err(data);
+            } else {
+                position=self.enqueue_position.0.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Add the element at the back, yielding to other threads while the queue is full
+    pub fn enqueue(&self, data:T) {
+        let mut data=data;
+        while let Err(rejected)=self.try_enqueue(data) {
+            data=rejected;
+            thread::yield_now();
+        }
+    }
+
+    /// Remove the element at the front, or return None when the queue is empty
+    pub fn try_dequeue(&self) -> Option<T> {
+        let mut position=self.dequeue_position.0.load(Ordering::Relaxed);
+        loop {
+            let slot=&self.slots[position & self.mask];
+            let sequence=slot.sequence.load(Ordering::Acquire);
+            let lap=sequence.wrapping_sub(position.wrapping_add(1)) as isize;
+            if lap == 0 {
+                match self.dequeue_position.0.compare_exchange_weak(position, position.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        let data=unsafe { (*slot.data.get()).assume_init_read() };
+                        slot.sequence.store(position.wrapping_add(self.mask+1), Ordering::Release);
+                        return Some(data);
+                    },
+                    Err(current) => position=current
+                }
+            } else if lap < 0 {
+                // The slot has not been filled for this lap yet
+                return None;
+            } else {
+                position=self.dequeue_position.0.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl<T> Drop for LockFreeQueue<T> {
+    fn drop(&mut self) {
+        while self.try_dequeue().is_some() {}
+    }
+}
+
+/// With exclusive access no other thread can make room, so enqueueing into a full queue panics instead of spinning forever
+impl<T> Enqueue<T> for LockFreeQueue<T> where T : Clone {
+    fn enqueue(&mut self, data:&T) {
+        if self.try_enqueue(data.clone()).is_err() {
+            panic!("Queue is full");
+        }
+    }
+}
+
+impl<T> Dequeue<T> for LockFreeQueue<T> where T : Clone {
+    fn dequeue(&mut self) -> Option<T> {
+        self.try_dequeue()
+    }
+}
+
+/// Yields to other threads while the queue is full, like LockFreeQueue::enqueue
+impl<T> Enqueue<T> for &LockFreeQueue<T> where T : Clone {
+    fn enqueue(&mut self, data:&T) {
+        LockFreeQueue::enqueue(self, data.clone());
+    }
+}
+
+/// Returns None when the queue is empty, without waiting
+impl<T> Dequeue<T> for &LockFreeQueue<T> where T : Clone {
+    fn dequeue(&mut self) -> Option<T> {
+        self.try_dequeue()
+    }
+}
+
+impl<T> fmt::Debug for LockFreeQueue<T> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LockFreeQueue")
+            .field("size", &self.size())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LockFreeQueue;
+    use data_structures::queue::{Enqueue, Dequeue};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    const PRODUCERS: usize = 4;
+    const CONSUMERS: usize = 4;
+    const PER_PRODUCER: usize = 20000;
+
+    /// Run producers and consumers through a queue of the given capacity and return everything the consumers received
+    fn stress(capacity:usize) -> Vec<usize> {
+        let queue=Arc::new(LockFreeQueue::new(capacity));
+        let received=Arc::new(AtomicUsize::new(0));
+        let producers:Vec<_>=(0..PRODUCERS).map(|producer| {
+            let queue=queue.clone();
+            thread::spawn(move || {
+                for i in 0..PER_PRODUCER {
+                    queue.enqueue(producer*PER_PRODUCER+i);
+                }
+            })
+        }).collect();
+        let consumers:Vec<_>=(0..CONSUMERS).map(|_| {
+            let (queue, received)=(queue.clone(), received.clone());
+            thread::spawn(move || {
+                let mut data=Vec::new();
+                let mut last_seen=[None; PRODUCERS];
+                while received.load(Ordering::SeqCst) < PRODUCERS*PER_PRODUCER {
+                    match queue.try_dequeue() {
+                        Some(value) => {
+                            // Elements of one producer come out in the order it enqueued them
+                            let producer=value/PER_PRODUCER;
+                            assert!(last_seen[producer] < Some(value));
+                            last_seen[producer]=Some(value);
+                            data.push(value);
+                            received.fetch_add(1, Ordering::SeqCst);
+                        },
+                        None => thread::yield_now()
+                    }
+                }
+                data
+            })
+        }).collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut data:Vec<usize>=consumers.into_iter().flat_map(|consumer| consumer.join().unwrap()).collect();
+        assert!(queue.is_empty());
+        data.sort();
+        data
+    }
+
+    #[test]
+    fn it_fails_without_waiting_when_full_or_empty() {
+        let queue=LockFreeQueue::new(3);
+        assert_eq!(queue.capacity(), 4);
+        assert_eq!(queue.try_dequeue(), None);
+        for i in 0..4 {
+            assert_eq!(queue.try_enqueue(i), Ok(()));
+        }
+        assert_eq!(queue.try_enqueue(4), Err(4));
+        assert_eq!(queue.size(), 4);
+        for lap in 0..10 {
+            assert_eq!(queue.try_dequeue(), Some(lap));
+            assert_eq!(queue.try_enqueue(lap+4), Ok(()));
+        }
+        assert_eq!((0..4).map(|_| queue.try_dequeue().unwrap()).collect::<Vec<i32>>(), vec![10, 11, 12, 13]);
+        assert_eq!(queue.try_dequeue(), None);
+    }
+
+    #[test]
+    fn it_keeps_at_least_two_slots() {
+        let queue=LockFreeQueue::new(0);
+        assert_eq!(queue.capacity(), 2);
+        assert_eq!((queue.try_enqueue(1), queue.try_enqueue(2), queue.try_enqueue(3)), (Ok(()), Ok(()), Err(3)));
+    }
+
+    #[test]
+    fn it_drops_the_elements_left() {
+        let shared=Arc::new(0);
+        let queue=LockFreeQueue::new(8);
+        for _ in 0..5 {
+            queue.try_enqueue(shared.clone()).unwrap();
+        }
+        queue.try_dequeue();
+        assert_eq!(Arc::strong_count(&shared), 5);
+        drop(queue);
+        assert_eq!(Arc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn it_passes_every_element_once_under_contention() {
+        assert_eq!(stress(1024), (0..PRODUCERS*PER_PRODUCER).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn it_passes_every_element_once_through_a_tiny_ring() {
+        assert_eq!(stress(2), (0..PRODUCERS*PER_PRODUCER).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "Queue is full")]
+    fn it_panics_when_enqueueing_into_a_full_queue_with_exclusive_access() {
+        let mut queue=LockFreeQueue::new(2);
+        for i in 0..3 {
+            Enqueue::enqueue(&mut queue, &i);
+        }
+    }
+
+    #[test]
+    fn it_implements_the_queue_traits() {
+        fn fill<Q>(queue:&mut Q, count:u32) where Q : Enqueue<u32> {
+            for i in 0..count {
+                queue.enqueue(&i);
+            }
+        }
+        let mut queue=LockFreeQueue::new(4);
+        fill(&mut queue, 2);
+        assert_eq!(Dequeue::dequeue(&mut queue), Some(0));
+        let shared=Arc::new(LockFreeQueue::new(4));
+        let producer={
+            let shared=shared.clone();
+            thread::spawn(move || fill(&mut &*shared, 100))
+        };
+        let mut received=Vec::new();
+        let mut shared_ref=&*shared;
+        while received.len() < 100 {
+            match Dequeue::dequeue(&mut shared_ref) {
+                Some(data) => received.push(data),
+                None => thread::yield_now()
+            }
+        }
+        producer.join().unwrap();
+        assert_eq!(received, (0..100).collect::<Vec<u32>>());
+        assert_eq!(format!("{:?}", queue), "LockFreeQueue { size: 1, capacity: 4 }");
+    }
+
+    extern crate test;
+    use self::test::Bencher;
+    use data_structures::bounded_queue::BoundedQueue;
+
+    #[bench]
+    fn bench_lock_free_queue_enqueue_dequeue(b: &mut Bencher) {
+        let queue=LockFreeQueue::new(1024);
+        for i in 0..512u32 {
+            queue.try_enqueue(i).unwrap();
+        }
+        b.iter(|| {
+            let value=queue.try_dequeue().unwrap();
+            queue.try_enqueue(value).unwrap();
+        });
+    }
+
+    #[bench]
+    fn bench_bounded_queue_enqueue_dequeue(b: &mut Bencher) {
+        let queue=BoundedQueue::new(1024);
+        for i in 0..512u32 {
+            queue.try_enqueue(i).unwrap();
+        }
+        b.iter(|| {
+            let value=queue.try_dequeue().unwrap();
+            queue.try_enqueue(value).unwrap();
+        });
+    }
+}
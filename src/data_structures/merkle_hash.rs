@@ -0,0 +1,189 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use blake2_rfc::blake2b::{blake2b, Blake2b};
+use data_structures::merkle_hasher::HashBytes;
+
+macro_rules! fixed_hash {
+    ($name:ident, $length:expr) => {
+        #[derive(Clone, Copy)]
+        pub struct $name(pub [u8; $length]);
+
+        impl $name {
+            /// Number of bytes in a hash
+            pub const LENGTH: usize = $length;
+
+            /// Return the hash holding a copy of the bytes, or None when they are not exactly LENGTH bytes long
+            pub fn from_slice(bytes:&[u8]) -> Option<$name> {
+                if bytes.len() != $length {
+                    return None;
+                }
+                let mut hash=[0u8; $length];
+                hash.copy_from_slice(bytes);
+                Some($name(hash))
+            }
+
+            pub fn as_bytes(&self) -> &[u8; $length] {
+                &self.0
+            }
+
+            /// Hash the bytes with Blake2b of the same output length
+            pub fn digest(bytes:&[u8]) -> $name {
+                $name::from_slice(blake2b($length, &[], bytes).as_bytes()).unwrap()
+            }
+
+            /// Blake2b of the two hashes concatenated, usable wherever a hash_for_two is expected
+            pub fn hash_for_two(left:&$name, right:&$name) -> $name {
+                let mut blake=Blake2b::new($length);
+                blake.update(&left.0);
+                blake.update(&right.0);
+                $name::from_slice(blake.finalize().as_bytes()).unwrap()
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> $name {
+                $name([0u8; $length])
+            }
+        }
+
+        /// Compares every byte whatever the first difference, so the time taken does not leak where two hashes differ
+        impl PartialEq for $name {
+            fn eq(&self, other:&$name) -> bool {
+                let difference=self.0.iter().zip(other.0.iter()).fold(0u8, |difference, (a, b)| difference | (a ^ b));
+                ::std::hint::black_box(difference) == 0
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state:&mut H) {
+                self.0.hash(state);
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl From<[u8; $length]> for $name {
+            fn from(bytes:[u8; $length]) -> $name {
+                $name(bytes)
+            }
+        }
+
+        impl HashBytes for $name {
+            fn hash_bytes(&self) -> &[u8] {
+                &self.0
+            }
+
+            fn from_hash_bytes(bytes:&[u8]) -> Option<$name> {
+                $name::from_slice(bytes)
+            }
+        }
+
+        /// Lower case hex
+        impl fmt::Display for $name {
+            fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+                for byte in self.0.iter() {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self)
+            }
+        }
+
+        /// Parse exactly 2*LENGTH hex digits, in either case
+        impl FromStr for $name {
+            type Err = &'static str;
+
+            fn from_str(hex:&str) -> Result<$name, &'static str> {
+                if hex.len() != 2*$length {
+                    return Err("Hex string has the wrong length");
+                }
+                // from_str_radix also takes a leading '+', so the digits are checked first
+                if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                    return Err("Invalid hex digit");
+                }
+                let mut hash=[0u8; $length];
+                for (i, byte) in hash.iter_mut().enumerate() {
+                    *byte=u8::from_str_radix(&hex[2*i..2*i+2], 16).map_err(|_| "Invalid hex digit")?;
+                }
+                Ok($name(hash))
+            }
+        }
+    }
+}
+
+fixed_hash!(Hash32, 32);
+fixed_hash!(Hash64, 64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structures::merkle_tree::{MerkleTree, DomainSeparated, Blake2bHasher};
+
+    fn leaves(count:usize) -> Vec<Hash32> {
+        (0..count).map(|i| Hash32::digest(&[i as u8])).collect()
+    }
+
+    #[test]
+    fn it_formats_and_parses_hex() {
+        let hash=Hash32::digest(b"abc");
+        let hex=hash.to_string();
+        assert_eq!(hex.len(), 64);
+        assert_eq!(hex.parse::<Hash32>(), Ok(hash));
+        assert_eq!(hex.to_uppercase().parse::<Hash32>(), Ok(hash));
+        assert_eq!(format!("{:?}", Hash64::default()), format!("Hash64({})", "00".repeat(64)));
+    }
+
+    #[test]
+    fn it_rejects_malformed_hex() {
+        assert!("00".parse::<Hash32>().is_err());
+        assert!("zz".repeat(32).parse::<Hash32>().is_err());
+        assert!("é".repeat(32).parse::<Hash32>().is_err());
+        assert!("+0".repeat(32).parse::<Hash32>().is_err());
+        assert!("0+".repeat(32).parse::<Hash32>().is_err());
+        assert!("00".repeat(32).parse::<Hash64>().is_err());
+    }
+
+    #[test]
+    fn it_compares_every_byte() {
+        let hash=Hash64::digest(b"abc");
+        let mut other=hash;
+        assert_eq!(hash, other);
+        other.0[63]^=1;
+        assert_ne!(hash, other);
+        assert_eq!(Hash32::from_slice(&[0u8; 31]), None);
+    }
+
+    #[test]
+    fn it_builds_a_merkle_tree_of_fixed_size_hashes() {
+        let hashes=leaves(7);
+        let merkle_tree:MerkleTree<Hash32>=MerkleTree::create(Hash32::hash_for_two, &hashes);
+        let byte_hashes:Vec<Vec<u8>>=hashes.iter().map(|hash| hash.as_ref().to_vec()).collect();
+        let byte_tree=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256(), &byte_hashes);
+        assert_eq!(merkle_tree.root().as_ref(), &byte_tree.root()[..]);
+        assert!(merkle_tree.proof(3).unwrap().verify(&merkle_tree.root(), &hashes[3], &(Hash32::hash_for_two as fn(&Hash32, &Hash32) -> Hash32)));
+
+        let decoded=MerkleTree::from_bytes(Hash32::hash_for_two, &merkle_tree.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.root(), merkle_tree.root());
+    }
+
+    #[test]
+    fn it_builds_a_domain_separated_tree_of_fixed_size_hashes() {
+        let hashes:Vec<Hash64>=(0..5).map(|i| Hash64::digest(&[i])).collect();
+        let merkle_tree=MerkleTree::create_with_hasher(DomainSeparated::new(Hash64::digest), &hashes);
+        let byte_hashes:Vec<Vec<u8>>=hashes.iter().map(|hash| hash.as_ref().to_vec()).collect();
+        let byte_tree=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_512().domain_separated(), &byte_hashes);
+        assert_eq!(merkle_tree.root().as_ref(), &byte_tree.root()[..]);
+    }
+}
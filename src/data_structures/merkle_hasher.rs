@@ -0,0 +1,339 @@
+use blake2_rfc::blake2b::Blake2b;
+
+/// Rule applied to the last node of a level when it has no sibling to be paired with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OddNodeRule {
+    /// Pair the node with itself, which is what `create` does
+    Duplicate,
+    /// Carry the node up unchanged, which gives the RFC 6962/9162 tree shape
+    Promote
+}
+
+/// Hashing scheme of a merkle tree: how leaves and internal nodes are hashed and how an odd node is carried up
+pub trait MerkleHasher<HashValueType> {
+    /// Hash a leaf value into the node stored at the bottom level of the tree
+    fn hash_leaf(&self, leaf:&HashValueType) -> HashValueType;
+    /// Hash two child nodes into their parent
+    fn hash_node(&self, left:&HashValueType, right:&HashValueType) -> HashValueType;
+    /// Return the rule applied to the last node of an odd length level
+    fn odd_node_rule(&self) -> OddNodeRule;
+    /// Return the identifier of this scheme that is stored along with encoded trees, or None when the
+    /// identifier could not tell it apart from other schemes, in which case its trees are not encoded
+    fn scheme_id(&self) -> Option<u8> {
+        match self.odd_node_rule() {
+            OddNodeRule::Duplicate => Some(SCHEME_LEGACY),
+            OddNodeRule::Promote => Some(SCHEME_DOMAIN_SEPARATED)
+        }
+    }
+    /// Return the width of the hashes of internal nodes when the scheme fixes it, to be checked against encoded trees
+    fn hash_width(&self) -> Option<usize> {
+        None
+    }
+}
+
+pub const SCHEME_LEGACY: u8 = 0;
+pub const SCHEME_DOMAIN_SEPARATED: u8 = 1;
+
+/// Conversion of hash values to and from the bytes they are encoded with
+pub trait HashBytes : Sized {
+    fn hash_bytes(&self) -> &[u8];
+    /// Return the hash value of the bytes, or None when they are not a valid hash value
+    fn from_hash_bytes(bytes:&[u8]) -> Option<Self>;
+}
+
+impl HashBytes for Vec<u8> {
+    fn hash_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn from_hash_bytes(bytes:&[u8]) -> Option<Vec<u8>> {
+        Some(bytes.to_vec())
+    }
+}
+
+/// The bare hash function of two hashes that merkle trees used to store
+pub type HashForTwo<HashValueType> = fn(&HashValueType, &HashValueType) -> HashValueType;
+
+/// The legacy Bitcoin-style scheme for any hash_for_two, be it a `HashForTwo` pointer or a closure:
+/// leaves are stored as given, nodes are hashed with hash_for_two and the last node of an odd level is duplicated
+impl<HashValueType, F> MerkleHasher<HashValueType> for F where F : Fn(&HashValueType, &HashValueType) -> HashValueType, HashValueType : Clone {
+    fn hash_leaf(&self, leaf:&HashValueType) -> HashValueType {
+        leaf.clone()
+    }
+
+    fn hash_node(&self, left:&HashValueType, right:&HashValueType) -> HashValueType {
+        self(left, right)
+    }
+
+    fn odd_node_rule(&self) -> OddNodeRule {
+        OddNodeRule::Duplicate
+    }
+}
+
+pub const LEAF_PREFIX: u8 = 0x00;
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// RFC 6962 style scheme that prefixes leaves with 0x00 and nodes with 0x01 before hashing them with digest,
+/// so an internal node can never pass as a leaf, and carries an odd node up instead of duplicating it
+pub struct DomainSeparated<HashValueType> {
+    digest: fn(&[u8]) -> HashValueType
+}
+
+impl<HashValueType> DomainSeparated<HashValueType> {
+    pub fn new(digest: fn(&[u8]) -> HashValueType) -> DomainSeparated<HashValueType> {
+        DomainSeparated { digest }
+    }
+}
+
+impl<HashValueType> MerkleHasher<HashValueType> for DomainSeparated<HashValueType> where HashValueType : AsRef<[u8]> {
+    fn hash_leaf(&self, leaf:&HashValueType) -> HashValueType {
+        let mut bytes=Vec::with_capacity(leaf.as_ref().len()+1);
+        bytes.push(LEAF_PREFIX);
+        bytes.extend_from_slice(leaf.as_ref());
+        (self.digest)(&bytes)
+    }
+
+    fn hash_node(&self, left:&HashValueType, right:&HashValueType) -> HashValueType {
+        let mut bytes=Vec::with_capacity(left.as_ref().len()+right.as_ref().len()+1);
+        bytes.push(NODE_PREFIX);
+        bytes.extend_from_slice(left.as_ref());
+        bytes.extend_from_slice(right.as_ref());
+        (self.digest)(&bytes)
+    }
+
+    fn odd_node_rule(&self) -> OddNodeRule {
+        OddNodeRule::Promote
+    }
+}
+
+/// Blake2b hasher over byte vector hashes, optionally keyed and personalized
+/// # Example
+///
+/// ```
+/// use mldsa_rust::data_structures::merkle_tree::{MerkleTree, Blake2bHasher};
+/// let hasher=Blake2bHasher::blake2b_256().with_key(b"secret").domain_separated();
+/// let merkle_tree=MerkleTree::create_with_hasher(hasher, &vec![vec![0u8; 32], vec![1u8; 32]]);
+/// assert_eq!(merkle_tree.root().len(), 32);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Blake2bHasher {
+    output_length: usize,
+    key: Vec<u8>,
+    personalization: Vec<u8>,
+    domain_separated: bool
+}
+
+impl Blake2bHasher {
+    /// Construct a hasher with the given output length in bytes, which must be between 1 and 64
+    pub fn new(output_length:usize) -> Blake2bHasher {
+        assert!((1..=64).contains(&output_length), "Blake2b output length must be between 1 and 64");
+        Blake2bHasher {
+            output_length,
+            key: Vec::new(),
+            personalization: Vec::new(),
+            domain_separated: false
+        }
+    }
+
+    /// Construct a hasher producing 32 byte hashes
+    pub fn blake2b_256() -> Blake2bHasher {
+        Blake2bHasher::new(32)
+    }
+
+    /// Construct a hasher producing 64 byte hashes
+    pub fn blake2b_512() -> Blake2bHasher {
+        Blake2bHasher::new(64)
+    }
+
+    /// Key every hash with the given key, which must not be longer than 64 bytes
+    pub fn with_key(mut self, key:&[u8]) -> Blake2bHasher {
+        assert!(key.len() <= 64, "Blake2b key must not be longer than 64 bytes");
+        self.key=key.to_vec();
+        self
+    }
+
+    /// Set the Blake2b personalization parameter, which must not be longer than 16 bytes and is padded with zeros
+    pub fn with_personalization(mut self, personalization:&[u8]) -> Blake2bHasher {
+        assert!(personalization.len() <= 16, "Blake2b personalization must not be longer than 16 bytes");
+        self.personalization=personalization.to_vec();
+        self
+    }
+
+    /// Switch to the scheme of `DomainSeparated`: 0x00/0x01 prefixes for leaves and nodes and promoted odd nodes
+    pub fn domain_separated(mut self) -> Blake2bHasher {
+        self.domain_separated=true;
+        self
+    }
+
+    /// Return the length of hashes this hasher produces
+    pub fn output_length(&self) -> usize {
+        self.output_length
+    }
+
+    /// Start a Blake2b state with the personalization in its parameter block, keyed as Blake2b::with_key does
+    fn state(&self) -> Blake2b {
+        let mut personalization=[0u8; 16];
+        personalization[..self.personalization.len()].copy_from_slice(&self.personalization);
+        let word=|bytes:&[u8]| bytes.iter().rev().fold(0u64, |word, &byte| (word << 8) | u64::from(byte));
+        let mut parameters=[0u64; 8];
+        parameters[0]=0x0101_0000 ^ ((self.key.len() as u64) << 8) ^ (self.output_length as u64);
+        parameters[6]=word(&personalization[..8]);
+        parameters[7]=word(&personalization[8..]);
+        let mut blake=Blake2b::with_parameter_block(&parameters);
+        if !self.key.is_empty() {
+            let mut key_block=[0u8; 128];
+            key_block[..self.key.len()].copy_from_slice(&self.key);
+            blake.update(&key_block);
+        }
+        blake
+    }
+
+    fn digest(&self, prefix:Option<u8>, parts:&[&Vec<u8>]) -> Vec<u8> {
+        let mut blake=self.state();
+        if let Some(prefix)=prefix {
+            blake.update(&[prefix]);
+        }
+        for part in parts {
+            blake.update(part);
+        }
+        blake.finalize().as_bytes().to_vec()
+    }
+}
+
+impl MerkleHasher<Vec<u8>> for Blake2bHasher {
+    fn hash_leaf(&self, leaf:&Vec<u8>) -> Vec<u8> {
+        if self.domain_separated {
+            self.digest(Some(LEAF_PREFIX), &[leaf])
+        } else {
+            leaf.clone()
+        }
+    }
+
+    fn hash_node(&self, left:&Vec<u8>, right:&Vec<u8>) -> Vec<u8> {
+        if self.domain_separated {
+            self.digest(Some(NODE_PREFIX), &[left, right])
+        } else {
+            self.digest(None, &[left, right])
+        }
+    }
+
+    fn odd_node_rule(&self) -> OddNodeRule {
+        if self.domain_separated {
+            OddNodeRule::Promote
+        } else {
+            OddNodeRule::Duplicate
+        }
+    }
+
+    /// Keyed and personalized hashers have no identifier, as the stored scheme id does not record a key or personalization
+    fn scheme_id(&self) -> Option<u8> {
+        if !self.key.is_empty() || !self.personalization.is_empty() {
+            return None;
+        }
+        match self.odd_node_rule() {
+            OddNodeRule::Duplicate => Some(SCHEME_LEGACY),
+            OddNodeRule::Promote => Some(SCHEME_DOMAIN_SEPARATED)
+        }
+    }
+
+    fn hash_width(&self) -> Option<usize> {
+        Some(self.output_length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structures::merkle_tree::MerkleTree;
+    use data_structures::merkle_hash::Hash32;
+    use blake2_rfc::blake2b::blake2b;
+
+    fn leaves(count:usize) -> Vec<Vec<u8>> {
+        (0..count).map(|i| blake2b(32, &[], &[i as u8]).as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn blake2b_256_matches_the_legacy_hash_for_two() {
+        let hashes=leaves(5);
+        let hash_two=|left:&Vec<u8>, right:&Vec<u8>| {
+            let mut blake=Blake2b::new(32);
+            blake.update(left);
+            blake.update(right);
+            blake.finalize().as_bytes().to_vec()
+        };
+        assert_eq!(MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256(), &hashes).root(), MerkleTree::create(hash_two, &hashes).root());
+    }
+
+    #[test]
+    fn blake2b_512_makes_64_byte_roots() {
+        let hashes=leaves(3);
+        let hasher=Blake2bHasher::blake2b_512();
+        assert_eq!(hasher.output_length(), 64);
+        assert_eq!(MerkleTree::create_with_hasher(hasher, &hashes).root().len(), 64);
+    }
+
+    #[test]
+    fn keyed_blake2b_hashes_nodes_with_the_key() {
+        let hashes=leaves(2);
+        let mut bytes=hashes[0].clone();
+        bytes.extend_from_slice(&hashes[1]);
+        let expected_root=blake2b(32, b"key", &bytes).as_bytes().to_vec();
+        assert_eq!(MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256().with_key(b"key"), &hashes).root(), expected_root);
+    }
+
+    #[test]
+    fn personalized_blake2b_makes_a_different_root() {
+        let hashes=leaves(4);
+        let plain_root=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256(), &hashes).root();
+        let personalized_root=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256().with_personalization(b"ledger"), &hashes).root();
+        assert_ne!(plain_root, personalized_root);
+    }
+
+    #[test]
+    fn personalization_is_the_blake2b_parameter() {
+        let abc=b"abc".to_vec();
+        let hasher=Blake2bHasher::blake2b_256().with_personalization(b"ledger");
+        assert_eq!(Hash32::from_slice(&hasher.digest(None, &[&abc])), "f12da928d859ac5b7154615f0de8a98141a18feb79334e981f638fca49183213".parse().ok());
+        let hasher=hasher.with_key(b"key");
+        assert_eq!(Hash32::from_slice(&hasher.digest(None, &[&abc])), "82f66463c71e6e7835783af468a277e5942db0496fecfdb003a4ada7c3744142".parse().ok());
+        assert_eq!(Hash32::from_slice(&hasher.digest(None, &[])), "76678aa551bd63ff555315a03281f51776369b31803b89e1e4cb5130df9b140c".parse().ok());
+    }
+
+    #[test]
+    fn personalization_does_not_run_into_the_input() {
+        let (b_data, data)=(b"Bdata".to_vec(), b"data".to_vec());
+        let split=Blake2bHasher::blake2b_256().with_personalization(b"A").digest(None, &[&b_data]);
+        let joined=Blake2bHasher::blake2b_256().with_personalization(b"AB").digest(None, &[&data]);
+        assert_ne!(split, joined);
+    }
+
+    #[test]
+    fn domain_separated_blake2b_matches_domain_separated_digest() {
+        fn digest(bytes:&[u8]) -> Vec<u8> {
+            blake2b(32, &[], bytes).as_bytes().to_vec()
+        }
+        let hashes=leaves(7);
+        let merkle_tree=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256().domain_separated(), &hashes);
+        assert_eq!(merkle_tree.root(), MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes).root());
+        for (index, leaf) in hashes.iter().enumerate() {
+            assert!(merkle_tree.proof(index).unwrap().verify(&merkle_tree.root(), leaf, merkle_tree.hasher()));
+        }
+    }
+
+    #[test]
+    fn closures_with_captured_state_hash_nodes() {
+        let hashes=leaves(3);
+        let key=b"captured".to_vec();
+        let keyed=move |left:&Vec<u8>, right:&Vec<u8>| {
+            let mut bytes=left.clone();
+            bytes.extend_from_slice(right);
+            blake2b(32, &key, &bytes).as_bytes().to_vec()
+        };
+        assert_eq!(MerkleTree::create_with_hasher(&keyed, &hashes).root(), MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256().with_key(b"captured"), &hashes).root());
+        let mut merkle_tree=MerkleTree::empty_tree_with_hasher(keyed);
+        for hash in hashes.iter() {
+            merkle_tree.append_hash(hash.clone());
+        }
+        assert_eq!(merkle_tree.proof(2).unwrap().path().len(), 2);
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+pub use data_structures::merkle_hasher::MerkleHasher;
+
+/// Append-only accumulator made of perfect binary trees, the mountains, whose sizes follow the bits of the leaf count
+///
+/// A node is addressed by its height above the leaves and its index within that height, so the node
+/// (height, index) covers the leaves from index<<height up to (index+1)<<height. Appending never changes
+/// an existing node, which is what makes proofs against historical roots possible.
+pub struct MerkleMountainRange<HashValueType, Hasher> {
+    hasher: Hasher,
+    nodes: HashMap<(usize, usize), HashValueType>,
+    leaf_count: usize,
+    pruned_before: usize
+}
+
+/// Inclusion proof of a leaf against the root of the range at a given leaf count
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MountainRangeProof<HashValueType> {
+    leaf_count: usize,
+    index: usize,
+    /// Siblings from the leaf up to the peak of its mountain
+    path: Vec<HashValueType>,
+    /// Peaks of the other mountains, left to right
+    peaks: Vec<HashValueType>
+}
+
+/// Return the (height, index) of every peak of a range with the given leaf count, left to right
+fn peak_positions(leaf_count:usize) -> Vec<(usize, usize)> {
+    let mut peaks=Vec::new();
+    let mut offset=0;
+    let mut height=0;
+    while (leaf_count >> height) > 1 {
+        height+=1;
+    }
+    loop {
+        if leaf_count & (1 << height) != 0 {
+            peaks.push((height, offset >> height));
+            offset+=1 << height;
+        }
+        if height == 0 {
+            break;
+        }
+        height-=1;
+    }
+    peaks
+}
+
+/// Hash the peaks into a single root from right to left
+fn bag_peaks<HashValueType, Hasher>(hasher:&Hasher, peaks:&[HashValueType]) -> Option<HashValueType>
+    where HashValueType : Clone, Hasher : MerkleHasher<HashValueType> {
+    let mut peaks=peaks.iter().rev();
+    let mut root=peaks.next()?.clone();
+    for peak in peaks {
+        root=hasher.hash_node(peak, &root);
+    }
+    Some(root)
+}
+
+impl<HashValueType, Hasher> MerkleMountainRange<HashValueType, Hasher> where HashValueType : Clone + PartialEq, Hasher : MerkleHasher<HashValueType> {
+    /// Construct an empty range that hashes with the given scheme
+    pub fn new(hasher:Hasher) -> MerkleMountainRange<HashValueType, Hasher> {
+        MerkleMountainRange {
+            hasher,
+            nodes: HashMap::new(),
+            leaf_count: 0,
+            pruned_before: 0
+        }
+    }
+
+    /// Return the number of leaves appended so far
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Return the number of nodes still stored
+    pub fn stored_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return the hashing scheme of this range
+    pub fn hasher(&self) -> &Hasher {
+        &self.hasher
+    }
+
+    /// Append a leaf, merge the mountains of equal height it completes and return the new root value
+    pub fn append(&mut self, leaf:HashValueType) -> HashValueType {
+        let mut height=0;
+        let mut index=self.leaf_count;
+        let mut node=self.hasher.hash_leaf(&leaf);
+        self.nodes.insert((height, index), node.clone());
+        while index % 2 == 1 {
+            node=self.hasher.hash_node(&self.nodes[&(height, index-1)], &node);
+            height+=1;
+            index>>=1;
+            self.nodes.insert((height, index), node.clone());
+        }
+        self.leaf_count+=1;
+        self.root().unwrap()
+    }
+
+    /// Return the peaks of the mountains, left to right
+    pub fn peaks(&self) -> Vec<HashValueType> {
+        peak_positions(self.leaf_count).iter().map(|position| self.nodes[position].clone()).collect()
+    }
+
+    /// Return the peaks bagged into a single root value, or None when the range is empty
+    pub fn root(&self) -> Option<HashValueType> {
+        bag_peaks(&self.hasher, &self.peaks())
+    }
+
+    /// Return the root value the range had when it held leaf_count leaves
+    pub fn root_at(&self, leaf_count:usize) -> Result<HashValueType, &'static str> {
+        if leaf_count == 0 || leaf_count > self.leaf_count {
+            return Err("Size out of range");
+        }
+        let peaks=self.nodes_at(&peak_positions(leaf_count))?;
+        Ok(bag_peaks(&self.hasher, &peaks).unwrap())
+    }
+
+    /// Build the proof of the leaf at the given index against the root the range had at leaf_count leaves
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::merkle_mountain_range::MerkleMountainRange;
+    /// fn hash_for_two_hashes(a:&u64, b:&u64) -> u64 {a.wrapping_mul(31).wrapping_add(*b)}
+    /// let mut range=MerkleMountainRange::new(hash_for_two_hashes);
+    /// for leaf in 0..5 {
+    ///     range.append(leaf);
+    /// }
+    /// let proof=range.proof(1, 3).unwrap();
+    /// assert!(proof.verify(&range.root_at(3).unwrap(), &1, range.hasher()));
+    /// ```
+    pub fn proof(&self, index:usize, leaf_count:usize) -> Result<MountainRangeProof<HashValueType>, &'static str> {
+        if leaf_count > self.leaf_count || index >= leaf_count {
+            return Err("Index out of range");
+        }
+        if index < self.pruned_before {
+            return Err("Leaf pruned");
+        }
+        let positions=peak_positions(leaf_count);
+        let (mountain_height, _)=positions.iter().cloned().find(|&(height, peak)| index >> height == peak).unwrap();
+        let mut path=Vec::with_capacity(mountain_height);
+        for height in 0..mountain_height {
+            path.push(self.nodes_at(&[(height, (index >> height) ^ 1)])?.remove(0));
+        }
+        let other_peaks:Vec<(usize, usize)>=positions.into_iter().filter(|&(height, peak)| index >> height != peak).collect();
+        Ok(MountainRangeProof {
+            leaf_count,
+            index,
+            path,
+            peaks: self.nodes_at(&other_peaks)?
+        })
+    }
+
+    /// Drop every node that is no longer needed to append or to prove a leaf at or after the given index
+    ///
+    /// A node is kept when it covers such a leaf or when it is the left sibling of a node that does,
+    /// which includes every peak and every peak of the historical roots that are still provable.
+    pub fn prune(&mut self, before:usize) {
+        let before=before.min(self.leaf_count);
+        self.nodes.retain(|&(height, index), _| {
+            ((index+1) << height) > before || (index % 2 == 0 && ((index+2) << height) > before)
+        });
+        self.pruned_before=self.pruned_before.max(before);
+    }
+
+    fn nodes_at(&self, positions:&[(usize, usize)]) -> Result<Vec<HashValueType>, &'static str> {
+        positions.iter().map(|position| self.nodes.get(position).cloned().ok_or("Node pruned")).collect()
+    }
+}
+
+impl<HashValueType> MountainRangeProof<HashValueType> where HashValueType : Clone + PartialEq {
+    /// Return the leaf count of the root this proof was built against
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Return the index of the leaf this proof was built for
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Check that the leaf hashes up to its peak and that the bagged peaks give the root
+    pub fn verify<Hasher>(&self, root:&HashValueType, leaf:&HashValueType, hasher:&Hasher) -> bool where Hasher : MerkleHasher<HashValueType> {
+        if self.index >= self.leaf_count {
+            return false;
+        }
+        let positions=peak_positions(self.leaf_count);
+        let mountain=match positions.iter().position(|&(height, peak)| self.index >> height == peak) {
+            Some(mountain) => mountain,
+            None => return false
+        };
+        if self.path.len() != positions[mountain].0 || self.peaks.len()+1 != positions.len() {
+            return false;
+        }
+        let mut node=hasher.hash_leaf(leaf);
+        for (height, sibling) in self.path.iter().enumerate() {
+            node = if (self.index >> height) % 2 == 1 {
+                hasher.hash_node(sibling, &node)
+            } else {
+                hasher.hash_node(&node, sibling)
+            };
+        }
+        let mut peaks=self.peaks.clone();
+        peaks.insert(mountain, node);
+        bag_peaks(hasher, &peaks).is_some_and(|bagged| bagged == *root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structures::merkle_hasher::Blake2bHasher;
+    use blake2_rfc::blake2b::blake2b;
+
+    fn leaves(count:usize) -> Vec<Vec<u8>> {
+        (0..count).map(|i| blake2b(32, &[], &[i as u8]).as_bytes().to_vec()).collect()
+    }
+
+    fn range_of(count:usize) -> MerkleMountainRange<Vec<u8>, Blake2bHasher> {
+        let mut range=MerkleMountainRange::new(Blake2bHasher::blake2b_256().domain_separated());
+        for leaf in leaves(count) {
+            range.append(leaf);
+        }
+        range
+    }
+
+    #[test]
+    fn it_places_peaks_by_the_bits_of_the_leaf_count() {
+        assert_eq!(peak_positions(0), vec![]);
+        assert_eq!(peak_positions(1), vec![(0, 0)]);
+        assert_eq!(peak_positions(6), vec![(2, 0), (1, 2)]);
+        assert_eq!(peak_positions(11), vec![(3, 0), (1, 4), (0, 10)]);
+        assert_eq!(range_of(11).peaks().len(), 3);
+    }
+
+    #[test]
+    fn it_bags_the_peaks_into_the_root() {
+        let hashes=leaves(3);
+        let hasher=Blake2bHasher::blake2b_256().domain_separated();
+        let left=hasher.hash_node(&hasher.hash_leaf(&hashes[0]), &hasher.hash_leaf(&hashes[1]));
+        let expected_root=hasher.hash_node(&left, &hasher.hash_leaf(&hashes[2]));
+        assert_eq!(range_of(3).root(), Some(expected_root));
+        assert_eq!(range_of(0).root(), None);
+    }
+
+    #[test]
+    fn it_keeps_every_historical_root() {
+        let range=range_of(20);
+        for count in 1..21 {
+            assert_eq!(range.root_at(count).unwrap(), range_of(count).root().unwrap());
+        }
+        assert!(range.root_at(0).is_err());
+        assert!(range.root_at(21).is_err());
+    }
+
+    #[test]
+    fn it_verifies_proofs_against_historical_roots() {
+        let hashes=leaves(13);
+        let range=range_of(13);
+        for count in 1..14 {
+            let root=range.root_at(count).unwrap();
+            for index in 0..count {
+                let proof=range.proof(index, count).unwrap();
+                assert!(proof.verify(&root, &hashes[index], range.hasher()));
+                assert!(!proof.verify(&root, &hashes[(index+1)%13], range.hasher()));
+            }
+        }
+        assert!(range.proof(3, 3).is_err());
+        assert!(range.proof(3, 14).is_err());
+    }
+
+    #[test]
+    fn it_prunes_old_nodes_but_keeps_recent_leaves_provable() {
+        let hashes=leaves(100);
+        let mut range=range_of(100);
+        let root=range.root().unwrap();
+        let stored_nodes=range.stored_nodes();
+        range.prune(90);
+        assert!(range.stored_nodes() < stored_nodes/4);
+        assert_eq!(range.root(), Some(root.clone()));
+        assert!(range.proof(89, 100).is_err());
+        for (index, leaf) in hashes.iter().enumerate().skip(90) {
+            assert!(range.proof(index, 100).unwrap().verify(&root, leaf, range.hasher()));
+            let historical_root=range.root_at(index+1).unwrap();
+            assert!(range.proof(index, index+1).unwrap().verify(&historical_root, leaf, range.hasher()));
+        }
+        let mut appended=hashes.clone();
+        for leaf in leaves(120)[100..].iter() {
+            range.append(leaf.clone());
+            appended.push(leaf.clone());
+        }
+        let mut unpruned=MerkleMountainRange::new(Blake2bHasher::blake2b_256().domain_separated());
+        for leaf in appended {
+            unpruned.append(leaf);
+        }
+        assert_eq!(range.root(), unpruned.root());
+    }
+}
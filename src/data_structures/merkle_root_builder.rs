@@ -0,0 +1,180 @@
+use std::io::{self, Read};
+pub use data_structures::merkle_hasher::{MerkleHasher, OddNodeRule, HashForTwo};
+
+/// Computes the root of MerkleTree::create_with_hasher from leaves pushed one at a time
+///
+/// Only the left nodes still waiting for a right sibling are kept, at most one per level,
+/// so the memory used is logarithmic in the number of leaves.
+pub struct MerkleRootBuilder<HashValueType, Hasher=HashForTwo<HashValueType>> {
+    hasher: Hasher,
+    /// The pending node at index h is the root of a complete subtree of 2^h leaves
+    pending: Vec<Option<HashValueType>>,
+    leaf_count: usize
+}
+
+impl<HashValueType, Hasher> MerkleRootBuilder<HashValueType, Hasher> where HashValueType : Clone, Hasher : MerkleHasher<HashValueType> {
+    /// Construct a builder that hashes with the given scheme
+    pub fn new(hasher:Hasher) -> MerkleRootBuilder<HashValueType, Hasher> {
+        MerkleRootBuilder {
+            hasher,
+            pending: Vec::new(),
+            leaf_count: 0
+        }
+    }
+
+    /// Return the number of leaves pushed so far
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Return the number of nodes held while waiting for their right sibling
+    pub fn pending_nodes(&self) -> usize {
+        self.pending.iter().filter(|node| node.is_some()).count()
+    }
+
+    /// Add the next leaf, merging every complete subtree it finishes
+    pub fn push(&mut self, leaf:HashValueType) {
+        let mut node=self.hasher.hash_leaf(&leaf);
+        let mut height=0;
+        while let Some(left)=self.pending.get_mut(height).and_then(|pending| pending.take()) {
+            node=self.hasher.hash_node(&left, &node);
+            height+=1;
+        }
+        if height == self.pending.len() {
+            self.pending.push(None);
+        }
+        self.pending[height]=Some(node);
+        self.leaf_count+=1;
+    }
+
+    /// Cut the reader into chunks of chunk_length bytes, the last one possibly shorter, and push the digest of each as a leaf
+    pub fn push_reader<R, F>(&mut self, reader:R, chunk_length:usize, digest:F) -> io::Result<()>
+        where R : Read, F : Fn(&[u8]) -> HashValueType {
+        let mut reader=reader;
+        let mut chunk=vec![0u8; chunk_length];
+        loop {
+            let mut filled=0;
+            while filled < chunk_length {
+                match reader.read(&mut chunk[filled..]) {
+                    Ok(0) => break,
+                    Ok(read) => filled+=read,
+                    Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {},
+                    Err(error) => return Err(error)
+                }
+            }
+            if filled == 0 {
+                return Ok(());
+            }
+            self.push(digest(&chunk[..filled]));
+            if filled < chunk_length {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Return the root of the leaves pushed so far, or None when there are none
+    ///
+    /// The pending nodes are closed from the lowest level up: a node left without a right sibling is
+    /// the last node of an odd level whenever a higher pending node sits to its left, and it is then
+    /// duplicated or promoted exactly like create does.
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::merkle_root_builder::MerkleRootBuilder;
+    /// use mldsa_rust::data_structures::merkle_tree::MerkleTree;
+    /// fn hash_for_two_hashes(a:&u64, b:&u64) -> u64 {a.wrapping_mul(31).wrapping_add(*b)}
+    /// let mut builder=MerkleRootBuilder::new(hash_for_two_hashes);
+    /// builder.extend(0..5);
+    /// assert_eq!(builder.root(), Some(MerkleTree::create(hash_for_two_hashes, &[0, 1, 2, 3, 4]).root()));
+    /// ```
+    pub fn root(&self) -> Option<HashValueType> {
+        let mut carry:Option<HashValueType>=None;
+        for (height, pending) in self.pending.iter().enumerate() {
+            let has_left_nodes=self.pending[height+1..].iter().any(|node| node.is_some());
+            carry=match (pending.as_ref(), carry.as_ref()) {
+                (Some(left), Some(right)) => Some(self.hasher.hash_node(left, right)),
+                (Some(node), None) | (None, Some(node)) if has_left_nodes => Some(self.odd_node(node)),
+                (node, None) | (None, node) => node.cloned()
+            };
+        }
+        carry
+    }
+
+    fn odd_node(&self, node:&HashValueType) -> HashValueType {
+        match self.hasher.odd_node_rule() {
+            OddNodeRule::Duplicate => self.hasher.hash_node(node, node),
+            OddNodeRule::Promote => node.clone()
+        }
+    }
+}
+
+impl<HashValueType, Hasher> Extend<HashValueType> for MerkleRootBuilder<HashValueType, Hasher> where HashValueType : Clone, Hasher : MerkleHasher<HashValueType> {
+    fn extend<I: IntoIterator<Item=HashValueType>>(&mut self, leaves:I) {
+        for leaf in leaves {
+            self.push(leaf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structures::merkle_hasher::Blake2bHasher;
+    use data_structures::merkle_tree::MerkleTree;
+    use blake2_rfc::blake2b::blake2b;
+
+    fn digest(bytes:&[u8]) -> Vec<u8> {
+        blake2b(32, &[], bytes).as_bytes().to_vec()
+    }
+
+    fn leaves(count:usize) -> Vec<Vec<u8>> {
+        (0..count).map(|i| digest(&[i as u8])).collect()
+    }
+
+    #[test]
+    fn it_has_no_root_without_leaves() {
+        let builder=MerkleRootBuilder::new(Blake2bHasher::blake2b_256());
+        assert_eq!(builder.root(), None);
+    }
+
+    #[test]
+    fn it_matches_create_with_the_duplicate_rule() {
+        for count in 1..70 {
+            let hashes=leaves(count);
+            let mut builder=MerkleRootBuilder::new(Blake2bHasher::blake2b_256());
+            builder.extend(hashes.clone());
+            assert_eq!(builder.root().unwrap(), MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256(), &hashes).root());
+        }
+    }
+
+    #[test]
+    fn it_matches_create_with_the_promote_rule() {
+        for count in 1..70 {
+            let hashes=leaves(count);
+            let mut builder=MerkleRootBuilder::new(Blake2bHasher::blake2b_256().domain_separated());
+            builder.extend(hashes.clone());
+            let merkle_tree=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256().domain_separated(), &hashes);
+            assert_eq!(builder.root().unwrap(), merkle_tree.root());
+        }
+    }
+
+    #[test]
+    fn it_keeps_a_logarithmic_number_of_pending_nodes() {
+        let mut builder=MerkleRootBuilder::new(Blake2bHasher::blake2b_256());
+        for (count, leaf) in leaves(5000).into_iter().enumerate() {
+            builder.push(leaf);
+            assert_eq!(builder.pending_nodes(), (count+1).count_ones() as usize);
+        }
+        assert_eq!(builder.leaf_count(), 5000);
+    }
+
+    #[test]
+    fn it_builds_a_root_from_the_chunks_of_a_reader() {
+        let bytes:Vec<u8>=(0..1000).map(|i| i as u8).collect();
+        let mut builder=MerkleRootBuilder::new(Blake2bHasher::blake2b_256());
+        builder.push_reader(&bytes[..], 64, digest).unwrap();
+        let hashes:Vec<Vec<u8>>=bytes.chunks(64).map(digest).collect();
+        assert_eq!(builder.leaf_count(), 16);
+        assert_eq!(builder.root().unwrap(), MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256(), &hashes).root());
+    }
+}
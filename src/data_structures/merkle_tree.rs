@@ -1,9 +1,45 @@
-pub struct MerkleTree<HashValueType>{
-    tree: Vec<HashValueType>,
-    hash_for_two: fn(&HashValueType, &HashValueType) -> HashValueType,
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+pub use data_structures::merkle_hash::{Hash32, Hash64};
+pub use data_structures::merkle_hasher::{MerkleHasher, OddNodeRule, HashForTwo, DomainSeparated, Blake2bHasher, HashBytes,
+                                         LEAF_PREFIX, NODE_PREFIX, SCHEME_LEGACY, SCHEME_DOMAIN_SEPARATED};
+
+/// Magic bytes that start an encoded merkle tree
+pub const FORMAT_MAGIC: &[u8; 4] = b"MKLT";
+/// Version of the encoding written by `to_bytes`
+pub const FORMAT_VERSION: u8 = 1;
+/// Magic, version, scheme id, hash width as u32 and leaf and node counts as u64, all big endian
+const FORMAT_HEADER_LENGTH: usize = 4+1+1+4+8+8;
+
+pub struct MerkleTree<HashValueType, Hasher=HashForTwo<HashValueType>>{
+    /// The first level holds the leaves and every next level their parents, up to the level holding the root
+    levels: Vec<Vec<HashValueType>>,
+    hasher: Hasher,
     input_length: usize
 }
 
+/// Side on which a sibling hash sits relative to the node on the path to the root
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    Left,
+    Right
+}
+
+/// Inclusion proof of a single leaf: the sibling hashes from the leaf level up to the root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof<HashValueType>{
+    index: usize,
+    path: Vec<(HashValueType, Position)>
+}
+
+/// Inclusion proof of several leaves at once: only the nodes that cannot be computed from the proven leaves
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleMultiProof<HashValueType>{
+    leaf_count: usize,
+    indices: Vec<usize>,
+    hashes: Vec<HashValueType>
+}
+
 macro_rules! next_level_len {
     ($x:expr) => ($x-($x>>1))
 }
@@ -21,17 +57,27 @@ macro_rules! tree_length_for_input {
     }}
 }
 
+/// Hash the parent of a node that has no sibling according to the odd node rule of the hasher
+macro_rules! hash_odd_node {
+    ($hasher:expr, $node:expr) => {{
+        match $hasher.odd_node_rule() {
+            OddNodeRule::Duplicate => $hasher.hash_node($node, $node),
+            OddNodeRule::Promote => $node.clone()
+        }
+    }}
+}
+
 macro_rules! create_next_level {
-    ($level:expr, $hash_for_two:expr) => {{
+    ($level:expr, $hasher:expr) => {{
         let length=$level.len();
         let mut i=0;
-        let mut return_val=Vec::with_capacity(($level.len()+1)/2);
+        let mut return_val=Vec::with_capacity($level.len().div_ceil(2));
         while (i+1)<length{
-            return_val.push($hash_for_two(&$level[i], &$level[i+1]));
+            return_val.push($hasher.hash_node(&$level[i], &$level[i+1]));
             i+=2;
         }
         if i<length {
-            return_val.push($hash_for_two(&$level[i], &$level[i]));
+            return_val.push(hash_odd_node!($hasher, &$level[i]));
         }
         return_val
     }}
@@ -41,54 +87,15 @@ macro_rules! create_next_level {
 #[macro_export]
 macro_rules! calculate_merkle_root {
     ($hash_for_two:expr, $hashes:expr) => {{
-        MerkleTree::create($hash_for_two, $hashes).root()
+        $crate::data_structures::merkle_tree::MerkleTree::create_with_hasher($hash_for_two, $hashes).root()
     }}
 }
 
 
 impl<HashValueType> MerkleTree<HashValueType> where HashValueType : Clone {
-    /// Append a new hash value and build new merkle tree and return the root value
-    pub fn append_hash(&mut self, new_hash:HashValueType) -> HashValueType {
-        if self.input_length == 0 {
-            self.tree=vec![new_hash];
-        } else {
-            let mut new_tree=Vec::with_capacity(tree_length_for_input!(self.input_length+1));
-            let mut level = self.tree[0..self.input_length].to_vec();
-            level.push(new_hash);
-            let mut rest = self.tree[self.input_length..].to_vec();
-            while level.len()>1 {
-                let mut next_level={
-                    if next_level_len!(level.len())>=rest.len() {
-                        rest[..].to_vec()
-                    } else {
-                        rest[0..next_level_len!(level.len())].to_vec()
-                    }
-                };
-                rest=rest[next_level.len()..].to_vec();
-                let last=level[level.len()-1].clone();
-                if level.len() % 2 == 1 {
-                    next_level.push((self.hash_for_two)(&last, &last));
-                } else if level.len()==2 {
-                    next_level.push((self.hash_for_two)(&level[level.len()-2], &level[level.len()-1]));
-                } else {
-                    let last_index=next_level.len()-1;
-                    next_level[last_index]=(self.hash_for_two)(&level[level.len()-2], &level[level.len()-1]);
-                }
-                new_tree.append(&mut level);
-                level=next_level;
-            }
-            new_tree.append(&mut level);
-            self.tree=new_tree;
-        }
-        self.input_length+=1;
-        assert_eq!(tree_length_for_input!(self.input_length), self.tree.len());
-        self.root()
-    }
-
     /// Construct a tree that has only one element containing the hash value of empty byte vector
     pub fn empty_tree(hash_for_two:fn(&HashValueType, &HashValueType)->HashValueType) -> MerkleTree<HashValueType> {
-        let tree=vec![];
-        MerkleTree{ tree, hash_for_two:hash_for_two, input_length:0 }
+        MerkleTree::empty_tree_with_hasher(hash_for_two)
     }
 
     /// Construct the merkle tree containing the input vector and their ancestors that are built with hash_for_two_hashes
@@ -103,40 +110,554 @@ impl<HashValueType> MerkleTree<HashValueType> where HashValueType : Clone {
     ///
     /// let merkle_tree=merkle_tree::MerkleTree::<Vec<u8>>::create(hash_for_two_hashes,&input);
     /// ```
-    pub fn create(hash: fn(&HashValueType, &HashValueType) -> HashValueType, hashes: &Vec<HashValueType>) -> MerkleTree<HashValueType>{
-        let hashes_len=hashes.len();
-        if hashes_len == 0 {
-            return MerkleTree::empty_tree(hash)
+    pub fn create(hash: fn(&HashValueType, &HashValueType) -> HashValueType, hashes: &[HashValueType]) -> MerkleTree<HashValueType>{
+        MerkleTree::create_with_hasher(hash, hashes)
+    }
+}
+
+impl<HashValueType, Hasher> MerkleTree<HashValueType, Hasher> where HashValueType : Clone, Hasher : MerkleHasher<HashValueType> {
+    /// Append a new hash value, rehash only the last node of every level and return the root value
+    pub fn append_hash(&mut self, new_hash:HashValueType) -> HashValueType {
+        let new_hash=self.hasher.hash_leaf(&new_hash);
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        self.levels[0].push(new_hash);
+        let mut depth=0;
+        while self.levels[depth].len() > 1 {
+            let level_len=self.levels[depth].len();
+            let parent = {
+                let level=&self.levels[depth];
+                if level_len % 2 == 1 {
+                    hash_odd_node!(self.hasher, &level[level_len-1])
+                } else {
+                    self.hasher.hash_node(&level[level_len-2], &level[level_len-1])
+                }
+            };
+            if depth+1 == self.levels.len() {
+                self.levels.push(Vec::new());
+            }
+            let next_level=&mut self.levels[depth+1];
+            if next_level.len() < next_level_len!(level_len) {
+                next_level.push(parent);
+            } else {
+                let last_index=next_level.len()-1;
+                next_level[last_index]=parent;
+            }
+            depth+=1;
         }
+        self.input_length+=1;
+        self.root()
+    }
 
-        let tree_size = tree_length_for_input!(hashes_len);
-        let mut tree: Vec<HashValueType> = Vec::with_capacity(tree_size);
+    /// Construct an empty tree that hashes with the given scheme
+    pub fn empty_tree_with_hasher(hasher:Hasher) -> MerkleTree<HashValueType, Hasher> {
+        MerkleTree{ levels:vec![], hasher, input_length:0 }
+    }
 
-        for hash in hashes {
-            tree.push(hash.clone());
+    /// Construct the merkle tree whose leaves and ancestors are hashed with the given scheme
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::merkle_tree::{MerkleTree, DomainSeparated};
+    /// fn digest(bytes:&[u8]) -> Vec<u8> {bytes.to_vec()}
+    /// let merkle_tree=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &vec![vec![7u8], vec![9u8]]);
+    /// assert_eq!(merkle_tree.root(), vec![1u8, 0, 7, 0, 9]);
+    /// ```
+    pub fn create_with_hasher(hasher:Hasher, hashes: &[HashValueType]) -> MerkleTree<HashValueType, Hasher>{
+        let hashes_len=hashes.len();
+        if hashes_len == 0 {
+            return MerkleTree::empty_tree_with_hasher(hasher)
         }
 
-        let mut level:Vec<HashValueType>= hashes.to_vec();
+        let mut levels=Vec::new();
+        let mut level:Vec<HashValueType>=hashes.iter().map(|hash| hasher.hash_leaf(hash)).collect();
         while level.len()>1 {
-            level=create_next_level!(&level, hash);
-            tree.append(&mut level.clone());
+            let next_level=create_next_level!(&level, hasher);
+            levels.push(level);
+            level=next_level;
         }
+        levels.push(level);
 
         MerkleTree {
-            tree,
-            hash_for_two:hash,
+            levels,
+            hasher,
             input_length:hashes_len
         }
     }
 
+    /// Replace the leaf at the given index, rehash the path above it and return the new root value
+    pub fn update_leaf(&mut self, index:usize, new_hash:HashValueType) -> Result<HashValueType, &'static str> {
+        self.update_many(&[(index, new_hash)])
+    }
+
+    /// Replace the leaves at the given indices and return the new root value
+    ///
+    /// Every ancestor is rehashed once even when several updated leaves share it. When an index
+    /// appears more than once, the last hash given for it wins. Nothing is updated if an index is out of range.
+    pub fn update_many(&mut self, updates:&[(usize, HashValueType)]) -> Result<HashValueType, &'static str> {
+        if updates.iter().any(|&(index, _)| index >= self.input_length) {
+            return Err("Index out of range");
+        }
+        let mut dirty=Vec::with_capacity(updates.len());
+        for (index, new_hash) in updates.iter() {
+            self.levels[0][*index]=self.hasher.hash_leaf(new_hash);
+            dirty.push(*index);
+        }
+        for depth in 1..self.levels.len() {
+            dirty=dirty.iter().map(|index| index>>1).collect();
+            dirty.sort();
+            dirty.dedup();
+            for parent in dirty.iter() {
+                self.rehash_parent(depth-1, *parent);
+            }
+        }
+        Ok(self.root())
+    }
+
+    fn rehash_parent(&mut self, depth:usize, parent:usize) {
+        let hash = {
+            let level=&self.levels[depth];
+            let left=parent<<1;
+            if left+1 < level.len() {
+                self.hasher.hash_node(&level[left], &level[left+1])
+            } else {
+                hash_odd_node!(self.hasher, &level[left])
+            }
+        };
+        self.levels[depth+1][parent]=hash;
+    }
+
+    /// Return the hashing scheme of this tree
+    pub fn hasher(&self) -> &Hasher {
+        &self.hasher
+    }
+
     /// Return the hash value stored in the root element of this tree
     pub fn root(&self) -> HashValueType {
-        self.tree[self.tree.len()-1].clone()
+        self.levels[self.levels.len()-1][0].clone()
     }
 
     /// Return the size of merkle tree
     pub fn size(&self) -> usize {
-        self.tree.len()
+        if self.input_length == 0 {
+            0
+        } else {
+            tree_length_for_input!(self.input_length)
+        }
+    }
+
+    /// Build the inclusion proof for the leaf at the given index
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::merkle_tree::{MerkleTree, MerkleProof};
+    /// fn hash_for_two_hashes(a:&u64, b:&u64) -> u64 {a.wrapping_mul(31).wrapping_add(*b)}
+    /// let merkle_tree=MerkleTree::create(hash_for_two_hashes, &vec![1, 2, 3]);
+    /// let proof=merkle_tree.proof(2).unwrap();
+    /// assert!(proof.verify(&merkle_tree.root(), &3, merkle_tree.hasher()));
+    /// ```
+    pub fn proof(&self, index:usize) -> Result<MerkleProof<HashValueType>, &'static str> {
+        if index >= self.input_length {
+            return Err("Index out of range");
+        }
+        let mut path=Vec::new();
+        let mut current=index;
+        for level in self.levels[..self.levels.len()-1].iter() {
+            let sibling=current^1;
+            if sibling >= level.len() {
+                // the last node of an odd level is paired with itself or carried up without a sibling
+                if self.hasher.odd_node_rule() == OddNodeRule::Duplicate {
+                    path.push((level[current].clone(), Position::Right));
+                }
+            } else if sibling < current {
+                path.push((level[sibling].clone(), Position::Left));
+            } else {
+                path.push((level[sibling].clone(), Position::Right));
+            }
+            current>>=1;
+        }
+        Ok(MerkleProof{ index, path })
+    }
+
+    /// Build one proof for the leaves at the given indices that shares the siblings their paths have in common
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::merkle_tree::MerkleTree;
+    /// fn hash_for_two_hashes(a:&u64, b:&u64) -> u64 {a.wrapping_mul(31).wrapping_add(*b)}
+    /// let merkle_tree=MerkleTree::create(hash_for_two_hashes, &vec![1, 2, 3, 4]);
+    /// let proof=merkle_tree.multiproof(&[0, 1]).unwrap();
+    /// assert_eq!(proof.hashes().len(), 1);
+    /// assert!(proof.verify(&merkle_tree.root(), &[(0, 1), (1, 2)], merkle_tree.hasher()));
+    /// ```
+    pub fn multiproof(&self, indices:&[usize]) -> Result<MerkleMultiProof<HashValueType>, &'static str> {
+        if indices.is_empty() || indices.iter().any(|index| *index >= self.input_length) {
+            return Err("Index out of range");
+        }
+        let mut known=indices.to_vec();
+        known.sort();
+        known.dedup();
+        let proven_indices=known.clone();
+        let mut hashes=Vec::new();
+        for level in self.levels[..self.levels.len()-1].iter() {
+            let mut i=0;
+            while i < known.len() {
+                let current=known[i];
+                if current % 2 == 1 {
+                    hashes.push(level[current-1].clone());
+                } else if i+1 < known.len() && known[i+1] == current+1 {
+                    i+=1;
+                } else if current+1 < level.len() {
+                    hashes.push(level[current+1].clone());
+                }
+                i+=1;
+            }
+            known=known.iter().map(|index| index>>1).collect();
+            known.dedup();
+        }
+        Ok(MerkleMultiProof{ leaf_count:self.input_length, indices:proven_indices, hashes })
+    }
+
+    /// Build the proof that the tree made of the first old_size leaves is a prefix of this tree
+    ///
+    /// The proof is the last leaf of the old tree followed by its siblings in this tree,
+    /// leaving out the nodes that have no sibling, so it does not depend on the odd node rule.
+    pub fn consistency_proof(&self, old_size:usize) -> Result<Vec<HashValueType>, &'static str> {
+        if old_size == 0 || old_size > self.input_length {
+            return Err("Size out of range");
+        }
+        let mut proof=vec![self.levels[0][old_size-1].clone()];
+        let mut current=old_size-1;
+        for level in self.levels[..self.levels.len()-1].iter() {
+            let sibling=current^1;
+            if sibling < level.len() {
+                proof.push(level[sibling].clone());
+            }
+            current>>=1;
+        }
+        Ok(proof)
+    }
+}
+
+/// Check a proof made by `consistency_proof` that the tree of old_size leaves is a prefix of the tree of new_size leaves
+/// # Example
+///
+/// ```
+/// use mldsa_rust::data_structures::merkle_tree::{self, MerkleTree};
+/// fn hash_for_two_hashes(a:&u64, b:&u64) -> u64 {a.wrapping_mul(31).wrapping_add(*b)}
+/// let old_tree=MerkleTree::create(hash_for_two_hashes, &vec![1, 2, 3]);
+/// let new_tree=MerkleTree::create(hash_for_two_hashes, &vec![1, 2, 3, 4, 5]);
+/// let proof=new_tree.consistency_proof(3).unwrap();
+/// assert!(merkle_tree::verify_consistency(&old_tree.root(), 3, &new_tree.root(), 5, &proof, &hash_for_two_hashes));
+/// ```
+pub fn verify_consistency<HashValueType, Hasher>(old_root:&HashValueType, old_size:usize, new_root:&HashValueType, new_size:usize,
+                                                 proof:&[HashValueType], hasher:&Hasher) -> bool
+    where HashValueType : Clone + PartialEq, Hasher : MerkleHasher<HashValueType> {
+    if old_size == 0 || old_size > new_size || proof.is_empty() {
+        return false;
+    }
+    let mut hashes=proof.iter();
+    let mut old_hash=hashes.next().unwrap().clone();
+    let mut new_hash=old_hash.clone();
+    let mut old_len=old_size;
+    let mut new_len=new_size;
+    let mut current=old_size-1;
+    while new_len > 1 {
+        if current % 2 == 1 {
+            // a left sibling covers old leaves only, so both trees share it
+            let sibling=match hashes.next() {
+                Some(sibling) => sibling,
+                None => return false
+            };
+            old_hash=hasher.hash_node(sibling, &old_hash);
+            new_hash=hasher.hash_node(sibling, &new_hash);
+        } else {
+            if old_len > 1 {
+                old_hash=hash_odd_node!(hasher, &old_hash);
+            }
+            if current+1 < new_len {
+                let sibling=match hashes.next() {
+                    Some(sibling) => sibling,
+                    None => return false
+                };
+                new_hash=hasher.hash_node(&new_hash, sibling);
+            } else {
+                new_hash=hash_odd_node!(hasher, &new_hash);
+            }
+        }
+        old_len=next_level_len!(old_len);
+        new_len=next_level_len!(new_len);
+        current>>=1;
+    }
+    hashes.next().is_none() && old_hash == *old_root && new_hash == *new_root
+}
+
+impl<HashValueType> MerkleProof<HashValueType> where HashValueType : Clone + PartialEq {
+    /// Return the index of the leaf this proof was built for
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Return the sibling hashes ordered from the leaf level up to the root
+    pub fn path(&self) -> &Vec<(HashValueType, Position)> {
+        &self.path
+    }
+
+    /// Check that the leaf hashes up to the given root along this path, without the tree itself
+    pub fn verify<Hasher>(&self, root:&HashValueType, leaf:&HashValueType, hasher:&Hasher) -> bool where Hasher : MerkleHasher<HashValueType> {
+        let mut current=hasher.hash_leaf(leaf);
+        for (sibling, position) in self.path.iter() {
+            current=match *position {
+                Position::Left => hasher.hash_node(sibling, &current),
+                Position::Right => hasher.hash_node(&current, sibling)
+            };
+        }
+        current == *root
+    }
+}
+
+impl<HashValueType> MerkleMultiProof<HashValueType> where HashValueType : Clone + PartialEq {
+    /// Return the number of leaves of the tree this proof was built from
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Return the sorted indices of the leaves this proof was built for
+    pub fn indices(&self) -> &Vec<usize> {
+        &self.indices
+    }
+
+    /// Return the nodes of the proof in the order the verifier consumes them, level by level from the leaves
+    pub fn hashes(&self) -> &Vec<HashValueType> {
+        &self.hashes
+    }
+
+    /// Check that the given (index, leaf) pairs, which must cover exactly the proven indices, hash up to the root
+    pub fn verify<Hasher>(&self, root:&HashValueType, leaves:&[(usize, HashValueType)], hasher:&Hasher) -> bool where Hasher : MerkleHasher<HashValueType> {
+        let mut known:Vec<(usize, HashValueType)>=leaves.iter().map(|(index, leaf)| (*index, hasher.hash_leaf(leaf))).collect();
+        known.sort_by_key(|&(index, _)| index);
+        known.dedup_by_key(|&mut (index, _)| index);
+        if known.len() != leaves.len() || known.iter().map(|&(index, _)| index).ne(self.indices.iter().cloned()) {
+            return false;
+        }
+        let mut hashes=self.hashes.iter();
+        let mut level_len=self.leaf_count;
+        while level_len > 1 {
+            let mut next_known=Vec::with_capacity(known.len());
+            let mut i=0;
+            while i < known.len() {
+                let (current, ref node)=known[i];
+                let parent = if current % 2 == 1 {
+                    match hashes.next() {
+                        Some(sibling) => hasher.hash_node(sibling, node),
+                        None => return false
+                    }
+                } else if i+1 < known.len() && known[i+1].0 == current+1 {
+                    i+=1;
+                    hasher.hash_node(node, &known[i].1)
+                } else if current+1 < level_len {
+                    match hashes.next() {
+                        Some(sibling) => hasher.hash_node(node, sibling),
+                        None => return false
+                    }
+                } else {
+                    hash_odd_node!(hasher, node)
+                };
+                next_known.push((current>>1, parent));
+                i+=1;
+            }
+            known=next_known;
+            level_len=next_level_len!(level_len);
+        }
+        hashes.next().is_none() && known.len() == 1 && known[0].1 == *root
+    }
+}
+
+/// Read the bytes as a big endian number
+fn read_be(bytes:&[u8]) -> u64 {
+    bytes.iter().fold(0u64, |number, byte| (number<<8) | u64::from(*byte))
+}
+
+/// Like `tree_length_for_input!`, but None instead of overflowing for a length read from untrusted input
+fn checked_tree_length(length:usize) -> Option<usize> {
+    let mut len=length;
+    let mut count:usize=0;
+    while len > 1 {
+        count=count.checked_add(len)?;
+        len=next_level_len!(len);
+    }
+    count.checked_add(1)
+}
+
+impl<HashValueType, Hasher> MerkleTree<HashValueType, Hasher> where HashValueType : Clone + HashBytes, Hasher : MerkleHasher<HashValueType> {
+    /// Encode the tree as a versioned header followed by the bytes of every node, level by level from the leaves
+    ///
+    /// The header holds the magic bytes, the format version, the scheme id of the hasher, the hash width
+    /// and the leaf and node counts. Every node must be as wide as the first leaf, and the hasher must have a scheme id.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, &'static str> {
+        let scheme_id=self.hasher.scheme_id().ok_or("Hasher has no scheme id to encode")?;
+        let width = if self.input_length == 0 { 0 } else { self.levels[0][0].hash_bytes().len() };
+        let node_count=self.size();
+        let mut bytes=Vec::with_capacity(FORMAT_HEADER_LENGTH+node_count*width);
+        bytes.extend_from_slice(FORMAT_MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.push(scheme_id);
+        bytes.extend_from_slice(&(width as u32).to_be_bytes());
+        bytes.extend_from_slice(&(self.input_length as u64).to_be_bytes());
+        bytes.extend_from_slice(&(node_count as u64).to_be_bytes());
+        for level in self.levels.iter() {
+            for node in level.iter() {
+                if node.hash_bytes().len() != width {
+                    return Err("Hashes differ in width");
+                }
+                bytes.extend_from_slice(node.hash_bytes());
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Decode a tree encoded by `to_bytes` without rehashing it
+    ///
+    /// The hasher must have the scheme id stored in the header, and the stored node count must be
+    /// the one `tree_length_for_input!` gives for the stored leaf count.
+    pub fn from_bytes(hasher:Hasher, bytes:&[u8]) -> Result<MerkleTree<HashValueType, Hasher>, &'static str> {
+        let (width, leaf_count, nodes_length)=MerkleTree::read_header(&hasher, bytes)?;
+        let nodes=&bytes[FORMAT_HEADER_LENGTH..];
+        // With a positive width this bounds every level by the bytes actually given, whatever the header claims
+        if nodes_length != nodes.len() {
+            return Err("Node bytes do not match the stored length");
+        }
+
+        let mut levels=Vec::new();
+        let mut offset=0;
+        let mut level_len=leaf_count;
+        while level_len > 0 {
+            let mut level=Vec::with_capacity(level_len);
+            for _ in 0..level_len {
+                level.push(HashValueType::from_hash_bytes(&nodes[offset..offset+width]).ok_or("Invalid hash bytes")?);
+                offset+=width;
+            }
+            levels.push(level);
+            level_len = if level_len == 1 { 0 } else { next_level_len!(level_len) };
+        }
+        Ok(MerkleTree { levels, hasher, input_length:leaf_count })
+    }
+
+    /// Check the header at the start of the bytes against the hasher and return the hash width, the leaf count
+    /// and the length of the node bytes that must follow
+    fn read_header(hasher:&Hasher, bytes:&[u8]) -> Result<(usize, usize, usize), &'static str> {
+        if bytes.len() < FORMAT_HEADER_LENGTH || &bytes[0..4] != FORMAT_MAGIC {
+            return Err("Not an encoded merkle tree");
+        }
+        if bytes[4] != FORMAT_VERSION {
+            return Err("Unsupported format version");
+        }
+        if Some(bytes[5]) != hasher.scheme_id() {
+            return Err("Scheme does not match the hasher");
+        }
+        let too_large=|_| "Stored length does not match the leaf count";
+        let width=usize::try_from(read_be(&bytes[6..10])).map_err(too_large)?;
+        let leaf_count=usize::try_from(read_be(&bytes[10..18])).map_err(too_large)?;
+        let node_count=usize::try_from(read_be(&bytes[18..26])).map_err(too_large)?;
+        if leaf_count > 0 && width == 0 {
+            return Err("Hash width must be positive");
+        }
+        // A single leaf may be stored unhashed, so only a tree with internal nodes shows the width of the hasher
+        if leaf_count > 1 && hasher.hash_width().is_some_and(|hash_width| hash_width != width) {
+            return Err("Hash width does not match the hasher");
+        }
+        let expected_count = if leaf_count == 0 { Some(0) } else { checked_tree_length(leaf_count) };
+        if expected_count != Some(node_count) {
+            return Err("Stored length does not match the leaf count");
+        }
+        let nodes_length=node_count.checked_mul(width).ok_or("Node bytes do not match the stored length")?;
+        Ok((width, leaf_count, nodes_length))
+    }
+
+    /// Write the encoding of `to_bytes` to the writer
+    pub fn write_to<W>(&self, mut writer:W) -> io::Result<()> where W : Write {
+        let bytes=self.to_bytes().map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        writer.write_all(&bytes)
+    }
+
+    /// Read a tree written by `write_to`, consuming only its bytes from the reader
+    pub fn read_from<R>(hasher:Hasher, mut reader:R) -> io::Result<MerkleTree<HashValueType, Hasher>> where R : Read {
+        let mut bytes=vec![0u8; FORMAT_HEADER_LENGTH];
+        reader.read_exact(&mut bytes)?;
+        let invalid=|error| io::Error::new(io::ErrorKind::InvalidData, error);
+        let (_, _, nodes_length)=MerkleTree::read_header(&hasher, &bytes).map_err(invalid)?;
+        // read_to_end grows the buffer as bytes arrive, so a false length in the header cannot make it allocate more than the reader holds
+        reader.take(nodes_length as u64).read_to_end(&mut bytes)?;
+        MerkleTree::from_bytes(hasher, &bytes).map_err(invalid)
+    }
+}
+
+/// Levels shorter than this are hashed on the calling thread
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_LEVEL_LENGTH: usize=1024;
+
+/// Split the items into chunks, map every chunk on its own scoped thread and join the results in order
+#[cfg(feature = "parallel")]
+fn map_chunks<T, U, F>(items:&[T], chunk_length:usize, map:F) -> Vec<U>
+    where T : Sync, U : Send, F : Fn(&[T]) -> Vec<U> + Sync {
+    let map=&map;
+    ::std::thread::scope(|scope| {
+        let handles:Vec<_>=items.chunks(chunk_length).map(|chunk| scope.spawn(move || map(chunk))).collect();
+        let mut return_val=Vec::with_capacity(items.len());
+        for handle in handles {
+            return_val.append(&mut handle.join().unwrap());
+        }
+        return_val
+    })
+}
+
+#[cfg(feature = "parallel")]
+impl<HashValueType, Hasher> MerkleTree<HashValueType, Hasher> where HashValueType : Clone + Send + Sync, Hasher : MerkleHasher<HashValueType> + Sync {
+    /// Construct the same tree as create_with_hasher, hashing every level on as many threads as the machine offers
+    pub fn create_parallel(hasher:Hasher, hashes:&[HashValueType]) -> MerkleTree<HashValueType, Hasher> {
+        let threads=::std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        MerkleTree::create_with_threads(hasher, hashes, threads)
+    }
+
+    /// Construct the same tree as create_with_hasher, splitting every level into at most the given number of chunks
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::merkle_tree::MerkleTree;
+    /// fn hash_for_two_hashes(a:&u64, b:&u64) -> u64 {a.wrapping_mul(31).wrapping_add(*b)}
+    /// let hashes:Vec<u64>=(0..5000).collect();
+    /// let merkle_tree=MerkleTree::create_with_threads(hash_for_two_hashes, &hashes, 4);
+    /// assert_eq!(merkle_tree.root(), MerkleTree::create_with_hasher(hash_for_two_hashes, &hashes).root());
+    /// ```
+    pub fn create_with_threads(hasher:Hasher, hashes:&[HashValueType], threads:usize) -> MerkleTree<HashValueType, Hasher> {
+        let hashes_len=hashes.len();
+        if threads <= 1 || hashes_len < PARALLEL_MIN_LEVEL_LENGTH {
+            return MerkleTree::create_with_hasher(hasher, hashes);
+        }
+
+        let mut levels=Vec::new();
+        let mut level=map_chunks(hashes, hashes_len.div_ceil(threads), |chunk| {
+            chunk.iter().map(|hash| hasher.hash_leaf(hash)).collect()
+        });
+        while level.len()>1 {
+            // Chunks of even length keep every pair inside one chunk, so only the last chunk can end on an odd node
+            let chunk_length=level.len().div_ceil(threads).next_multiple_of(2);
+            let next_level=if level.len() < PARALLEL_MIN_LEVEL_LENGTH {
+                create_next_level!(&level, hasher)
+            } else {
+                map_chunks(&level, chunk_length, |chunk| create_next_level!(chunk, hasher))
+            };
+            levels.push(level);
+            level=next_level;
+        }
+        levels.push(level);
+
+        MerkleTree {
+            levels,
+            hasher,
+            input_length:hashes_len
+        }
     }
 }
 
@@ -173,7 +694,7 @@ mod tests {
         let bytes = vec![0xAA; 32];
         let hash = blake2b(32, &[], &bytes);//hash(&bytes, 32);
         let expected_root = hash.as_bytes();
-        assert_eq!(calculate_merkle_root!(hash_two, &vec![hash.as_bytes().to_vec()]), expected_root);
+        assert_eq!(calculate_merkle_root!(hash_two, &[hash.as_bytes().to_vec()]), expected_root);
     }
 
     #[test]
@@ -259,4 +780,406 @@ mod tests {
 
         assert_eq!(merkle_tree.root(), expected_root);
     }
+
+    fn leaves(count:usize) -> Vec<Vec<u8>> {
+        (0..count).map(|i| blake2b(32, &[], &[i as u8]).as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn it_verifies_proofs_for_every_leaf() {
+        for count in 1..10 {
+            let hashes=leaves(count);
+            let merkle_tree=MerkleTree::create(hash_two, &hashes);
+            let root=merkle_tree.root();
+            for (index, leaf) in hashes.iter().enumerate() {
+                let proof=merkle_tree.proof(index).unwrap();
+                assert_eq!(proof.index(), index);
+                assert!(proof.verify(&root, leaf, &hash_two));
+            }
+        }
+    }
+
+    #[test]
+    fn it_rejects_a_proof_for_a_wrong_leaf() {
+        let hashes=leaves(5);
+        let merkle_tree=MerkleTree::create(hash_two, &hashes);
+        let proof=merkle_tree.proof(1).unwrap();
+        assert!(!proof.verify(&merkle_tree.root(), &hashes[2], &hash_two));
+    }
+
+    #[test]
+    fn it_pairs_the_odd_last_leaf_with_itself_in_a_proof() {
+        let hashes=leaves(3);
+        let merkle_tree=MerkleTree::create(hash_two, &hashes);
+        let proof=merkle_tree.proof(2).unwrap();
+        assert_eq!(proof.path()[0], (hashes[2].clone(), Position::Right));
+        assert_eq!(proof.path()[1], (hash_two(&hashes[0], &hashes[1]), Position::Left));
+    }
+
+    #[test]
+    fn it_fails_to_prove_an_index_out_of_range() {
+        let merkle_tree=MerkleTree::create(hash_two, &leaves(3));
+        assert!(merkle_tree.proof(3).is_err());
+        assert!(MerkleTree::create(hash_two, &Vec::new()).proof(0).is_err());
+    }
+
+    #[test]
+    fn it_appends_to_the_same_root_as_create() {
+        let hashes=leaves(12);
+        let mut merkle_tree=MerkleTree::create(hash_two, &Vec::new());
+        for (index, hash) in hashes.iter().enumerate() {
+            let root=merkle_tree.append_hash(hash.clone());
+            assert_eq!(root, calculate_merkle_root!(hash_two, &hashes[..index+1]));
+        }
+    }
+
+    #[test]
+    fn it_verifies_consistency_between_every_pair_of_sizes() {
+        let hashes=leaves(12);
+        for new_size in 1..hashes.len()+1 {
+            let mut new_tree=MerkleTree::create(hash_two, &hashes[..1]);
+            for hash in hashes[1..new_size].iter() {
+                new_tree.append_hash(hash.clone());
+            }
+            for old_size in 1..new_size+1 {
+                let old_root=calculate_merkle_root!(hash_two, &hashes[..old_size]);
+                let proof=new_tree.consistency_proof(old_size).unwrap();
+                assert!(verify_consistency(&old_root, old_size, &new_tree.root(), new_size, &proof, &hash_two));
+            }
+        }
+    }
+
+    #[test]
+    fn it_rejects_consistency_against_a_different_history() {
+        let hashes=leaves(7);
+        let new_tree=MerkleTree::create(hash_two, &hashes);
+        let mut forged=hashes[..4].to_vec();
+        forged[1]=hashes[6].clone();
+        let forged_root=calculate_merkle_root!(hash_two, &forged);
+        let proof=new_tree.consistency_proof(4).unwrap();
+        assert!(!verify_consistency(&forged_root, 4, &new_tree.root(), 7, &proof, &hash_two));
+        assert!(!verify_consistency(&new_tree.root(), 7, &new_tree.root(), 7, &proof, &hash_two));
+        assert!(!verify_consistency(&forged_root, 4, &new_tree.root(), 7, &proof[..1], &hash_two));
+    }
+
+    #[test]
+    fn it_verifies_consistency_with_the_promote_rule() {
+        let hashes=leaves(9);
+        let hasher=DomainSeparated::new(digest);
+        let new_tree=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes);
+        for old_size in 1..hashes.len()+1 {
+            let old_root=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes[..old_size]).root();
+            let proof=new_tree.consistency_proof(old_size).unwrap();
+            assert!(verify_consistency(&old_root, old_size, &new_tree.root(), 9, &proof, &hasher));
+        }
+        let old_root=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes[..3]).root();
+        let proof=new_tree.consistency_proof(3).unwrap();
+        assert!(!verify_consistency(&old_root, 3, &new_tree.root(), 9, &proof, &hash_two));
+    }
+
+    #[test]
+    fn it_fails_to_build_a_consistency_proof_for_a_size_out_of_range() {
+        let merkle_tree=MerkleTree::create(hash_two, &leaves(3));
+        assert!(merkle_tree.consistency_proof(0).is_err());
+        assert!(merkle_tree.consistency_proof(4).is_err());
+    }
+
+    fn digest(bytes:&[u8]) -> Vec<u8> {
+        blake2b(HASH_LENGTH, &[], bytes).as_bytes().to_vec()
+    }
+
+    #[test]
+    fn it_prefixes_leaves_and_nodes_when_domain_separated() {
+        let hashes=leaves(2);
+        let merkle_tree=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes);
+        let mut leaf_0=vec![LEAF_PREFIX];
+        leaf_0.extend_from_slice(&hashes[0]);
+        let mut leaf_1=vec![LEAF_PREFIX];
+        leaf_1.extend_from_slice(&hashes[1]);
+        let mut node=vec![NODE_PREFIX];
+        node.extend_from_slice(&digest(&leaf_0));
+        node.extend_from_slice(&digest(&leaf_1));
+        assert_eq!(merkle_tree.root(), digest(&node));
+    }
+
+    #[test]
+    fn it_promotes_the_odd_node_when_domain_separated() {
+        let hashes=leaves(3);
+        let hasher=DomainSeparated::new(digest);
+        let merkle_tree=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes);
+        let left=hasher.hash_node(&hasher.hash_leaf(&hashes[0]), &hasher.hash_leaf(&hashes[1]));
+        assert_eq!(merkle_tree.root(), hasher.hash_node(&left, &hasher.hash_leaf(&hashes[2])));
+        assert_eq!(merkle_tree.proof(2).unwrap().path().len(), 1);
+    }
+
+    #[test]
+    fn it_tells_a_duplicated_last_leaf_apart_when_domain_separated() {
+        let hashes=leaves(3);
+        let mut duplicated=hashes.clone();
+        duplicated.push(hashes[2].clone());
+        assert_eq!(calculate_merkle_root!(hash_two, &hashes), calculate_merkle_root!(hash_two, &duplicated));
+        assert_ne!(MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes).root(),
+                   MerkleTree::create_with_hasher(DomainSeparated::new(digest), &duplicated).root());
+    }
+
+    #[test]
+    fn it_rejects_an_internal_node_as_a_leaf_when_domain_separated() {
+        let hashes=leaves(4);
+        let hasher=DomainSeparated::new(digest);
+        let merkle_tree=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes);
+        let node=hasher.hash_node(&hasher.hash_leaf(&hashes[0]), &hasher.hash_leaf(&hashes[1]));
+        let mut proof=merkle_tree.proof(0).unwrap();
+        proof.path.remove(0);
+        assert!(!proof.verify(&merkle_tree.root(), &node, &hasher));
+        for (index, leaf) in hashes.iter().enumerate() {
+            assert!(merkle_tree.proof(index).unwrap().verify(&merkle_tree.root(), leaf, &hasher));
+        }
+    }
+
+    #[test]
+    fn it_appends_to_the_same_root_as_create_when_domain_separated() {
+        let hashes=leaves(12);
+        let mut merkle_tree=MerkleTree::empty_tree_with_hasher(DomainSeparated::new(digest));
+        for (index, hash) in hashes.iter().enumerate() {
+            let root=merkle_tree.append_hash(hash.clone());
+            assert_eq!(root, MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes[..index+1]).root());
+        }
+    }
+
+    #[test]
+    fn it_calculates_a_merkle_root_with_a_hasher() {
+        let hashes=leaves(5);
+        assert_eq!(calculate_merkle_root!(Blake2bHasher::blake2b_256(), &hashes), calculate_merkle_root!(hash_two, &hashes));
+    }
+
+    extern crate test;
+    use self::test::Bencher;
+
+    #[bench]
+    fn bench_append_hash(b: &mut Bencher) {
+        let mut merkle_tree=MerkleTree::create(hash_two, &leaves(100000));
+        b.iter(|| {
+            merkle_tree.append_hash(vec![0xAA; 32]);
+        });
+    }
+
+    #[bench]
+    fn bench_append_by_rebuilding(b: &mut Bencher) {
+        let mut hashes=leaves(100000);
+        b.iter(|| {
+            hashes.push(vec![0xAA; 32]);
+            MerkleTree::create_with_hasher(hash_two as HashForTwo<Vec<u8>>, &hashes).root()
+        });
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn it_builds_the_same_tree_in_parallel_for_random_inputs() {
+        extern crate rand;
+        let random=|| rand::random::<usize>();
+        for _ in 0..20 {
+            let count=random()%5000+1;
+            let threads=random()%8+1;
+            let hashes:Vec<Vec<u8>>=(0..count).map(|_| (0..HASH_LENGTH).map(|_| random() as u8).collect()).collect();
+            let serial=MerkleTree::create(hash_two, &hashes);
+            let parallel=MerkleTree::create_with_threads(hash_two as HashForTwo<Vec<u8>>, &hashes, threads);
+            assert_eq!(parallel.root(), serial.root());
+            assert_eq!(parallel.to_bytes(), serial.to_bytes());
+            let hasher=Blake2bHasher::blake2b_256().domain_separated();
+            assert_eq!(MerkleTree::create_with_threads(hasher.clone(), &hashes, threads).root(), MerkleTree::create_with_hasher(hasher, &hashes).root());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[bench]
+    fn bench_create_parallel(b: &mut Bencher) {
+        let hashes=leaves(100000);
+        b.iter(|| MerkleTree::create_parallel(hash_two as HashForTwo<Vec<u8>>, &hashes).root());
+    }
+
+    #[bench]
+    fn bench_create(b: &mut Bencher) {
+        let hashes=leaves(100000);
+        b.iter(|| MerkleTree::create(hash_two, &hashes).root());
+    }
+
+    #[test]
+    fn it_updates_a_leaf_to_the_same_root_as_create() {
+        for count in 1..10 {
+            let mut hashes=leaves(count);
+            let mut merkle_tree=MerkleTree::create(hash_two, &hashes);
+            for index in 0..count {
+                hashes[index]=vec![index as u8; 32];
+                let root=merkle_tree.update_leaf(index, hashes[index].clone()).unwrap();
+                assert_eq!(root, calculate_merkle_root!(hash_two, &hashes));
+            }
+        }
+    }
+
+    #[test]
+    fn it_updates_many_leaves_to_the_same_root_as_create() {
+        let mut hashes=leaves(11);
+        let mut merkle_tree=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes);
+        let updates=vec![(10, vec![1u8; 32]), (3, vec![2u8; 32]), (2, vec![3u8; 32]), (3, vec![4u8; 32])];
+        for (index, hash) in updates.iter() {
+            hashes[*index]=hash.clone();
+        }
+        let root=merkle_tree.update_many(&updates).unwrap();
+        assert_eq!(root, MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes).root());
+        assert!(merkle_tree.proof(3).unwrap().verify(&root, &vec![4u8; 32], merkle_tree.hasher()));
+    }
+
+    #[test]
+    fn it_fails_to_update_a_leaf_out_of_range() {
+        let hashes=leaves(3);
+        let mut merkle_tree=MerkleTree::create(hash_two, &hashes);
+        let root=merkle_tree.root();
+        let error=merkle_tree.update_leaf(3, vec![0u8; 32]).unwrap_err();
+        assert!(merkle_tree.update_many(&[(0, vec![0u8; 32]), (5, vec![0u8; 32])]).is_err());
+        assert_eq!(merkle_tree.root(), root);
+        merkle_tree.append_hash(vec![0u8; 32]);
+        assert_eq!(error, "Index out of range");
+    }
+
+    #[test]
+    fn it_verifies_multiproofs_for_every_pair_of_leaves() {
+        for count in 1..10 {
+            let hashes=leaves(count);
+            let merkle_tree=MerkleTree::create(hash_two, &hashes);
+            let root=merkle_tree.root();
+            for first in 0..count {
+                for second in first..count {
+                    let proof=merkle_tree.multiproof(&[second, first]).unwrap();
+                    let proven=vec![(first, hashes[first].clone()), (second, hashes[second].clone())];
+                    if first == second {
+                        assert!(proof.verify(&root, &proven[..1], &hash_two));
+                    } else {
+                        assert!(proof.verify(&root, &proven, &hash_two));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_shares_siblings_in_a_multiproof() {
+        let hashes=leaves(8);
+        let merkle_tree=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes);
+        let proof=merkle_tree.multiproof(&[0, 1, 2, 3]).unwrap();
+        assert_eq!(proof.hashes().len(), 1);
+        let proof=merkle_tree.multiproof(&[0, 2, 5]).unwrap();
+        assert_eq!(proof.hashes().len(), 4);
+        let proven:Vec<(usize, Vec<u8>)>=vec![5, 0, 2].into_iter().map(|index| (index, hashes[index].clone())).collect();
+        assert!(proof.verify(&merkle_tree.root(), &proven, merkle_tree.hasher()));
+    }
+
+    #[test]
+    fn it_rejects_a_multiproof_for_other_leaves() {
+        let hashes=leaves(7);
+        let merkle_tree=MerkleTree::create(hash_two, &hashes);
+        let root=merkle_tree.root();
+        let proof=merkle_tree.multiproof(&[1, 6]).unwrap();
+        assert!(!proof.verify(&root, &[(1, hashes[1].clone()), (6, hashes[5].clone())], &hash_two));
+        assert!(!proof.verify(&root, &[(1, hashes[1].clone()), (5, hashes[5].clone())], &hash_two));
+        assert!(!proof.verify(&root, &[(1, hashes[1].clone())], &hash_two));
+        assert!(!proof.verify(&root, &[(1, hashes[1].clone()), (1, hashes[1].clone()), (6, hashes[6].clone())], &hash_two));
+        assert!(merkle_tree.multiproof(&[]).is_err());
+        assert!(merkle_tree.multiproof(&[7]).is_err());
+    }
+
+    #[test]
+    fn it_encodes_and_decodes_trees_of_every_size() {
+        for count in 0..10 {
+            let hashes=leaves(count);
+            let merkle_tree=MerkleTree::create(hash_two, &hashes);
+            let bytes=merkle_tree.to_bytes().unwrap();
+            assert_eq!(&bytes[0..4], FORMAT_MAGIC);
+            assert_eq!(bytes.len(), FORMAT_HEADER_LENGTH+merkle_tree.size()*HASH_LENGTH*usize::from(count > 0));
+            let mut decoded=MerkleTree::from_bytes(hash_two as HashForTwo<Vec<u8>>, &bytes).unwrap();
+            assert_eq!(decoded.levels, merkle_tree.levels);
+            decoded.append_hash(vec![0xAA; 32]);
+            let mut appended=hashes.clone();
+            appended.push(vec![0xAA; 32]);
+            assert_eq!(decoded.root(), calculate_merkle_root!(hash_two, &appended));
+        }
+    }
+
+    #[test]
+    fn it_writes_and_reads_a_tree_through_io() {
+        let hashes=leaves(6);
+        let merkle_tree=MerkleTree::create_with_hasher(DomainSeparated::new(digest), &hashes);
+        let mut buffer=Vec::new();
+        merkle_tree.write_to(&mut buffer).unwrap();
+        buffer.extend_from_slice(b"trailing");
+        let mut reader=&buffer[..];
+        let decoded=MerkleTree::read_from(DomainSeparated::new(digest), &mut reader).unwrap();
+        assert_eq!(decoded.root(), merkle_tree.root());
+        assert_eq!(reader, b"trailing");
+    }
+
+    #[test]
+    fn it_rejects_corrupted_encodings() {
+        let merkle_tree=MerkleTree::create(hash_two, &leaves(5));
+        let bytes=merkle_tree.to_bytes().unwrap();
+        let decode=|bytes:&[u8]| MerkleTree::from_bytes(hash_two as HashForTwo<Vec<u8>>, bytes).err();
+
+        let mut wrong_magic=bytes.clone();
+        wrong_magic[0]=b'X';
+        assert_eq!(decode(&wrong_magic), Some("Not an encoded merkle tree"));
+        let mut wrong_version=bytes.clone();
+        wrong_version[4]=FORMAT_VERSION+1;
+        assert_eq!(decode(&wrong_version), Some("Unsupported format version"));
+        let mut wrong_leaf_count=bytes.clone();
+        wrong_leaf_count[17]=6;
+        assert_eq!(decode(&wrong_leaf_count), Some("Stored length does not match the leaf count"));
+        assert_eq!(decode(&bytes[..bytes.len()-1]), Some("Node bytes do not match the stored length"));
+        assert!(MerkleTree::from_bytes(DomainSeparated::new(digest), &bytes).is_err());
+        assert!(MerkleTree::read_from(hash_two as HashForTwo<Vec<u8>>, &bytes[..bytes.len()-1]).is_err());
+    }
+
+    #[test]
+    fn it_encodes_only_blake2b_trees_it_can_tell_apart() {
+        let hashes=leaves(5);
+        let keyed=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256().with_key(b"key"), &hashes);
+        assert_eq!(keyed.to_bytes().err(), Some("Hasher has no scheme id to encode"));
+        let personalized=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_256().with_personalization(b"ledger"), &hashes);
+        assert!(personalized.write_to(Vec::new()).is_err());
+        let plain_bytes=MerkleTree::create(hash_two, &hashes).to_bytes().unwrap();
+        assert_eq!(MerkleTree::from_bytes(Blake2bHasher::blake2b_256().with_key(b"key"), &plain_bytes).err(), Some("Scheme does not match the hasher"));
+        assert!(MerkleTree::from_bytes(Blake2bHasher::blake2b_256(), &plain_bytes).is_ok());
+
+        let wide_bytes=MerkleTree::create_with_hasher(Blake2bHasher::blake2b_512().domain_separated(), &hashes).to_bytes().unwrap();
+        assert_eq!(MerkleTree::from_bytes(Blake2bHasher::blake2b_256().domain_separated(), &wide_bytes).err(), Some("Hash width does not match the hasher"));
+        assert!(MerkleTree::from_bytes(Blake2bHasher::blake2b_512().domain_separated(), &wide_bytes).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_malformed_headers_without_allocating_for_them() {
+        let header=|width:u32, leaf_count:u64, node_count:u64| {
+            let mut bytes=FORMAT_MAGIC.to_vec();
+            bytes.push(FORMAT_VERSION);
+            bytes.push((hash_two as HashForTwo<Vec<u8>>).scheme_id().unwrap());
+            bytes.extend_from_slice(&width.to_be_bytes());
+            bytes.extend_from_slice(&leaf_count.to_be_bytes());
+            bytes.extend_from_slice(&node_count.to_be_bytes());
+            bytes
+        };
+        let decode=|bytes:&[u8]| MerkleTree::from_bytes(hash_two as HashForTwo<Vec<u8>>, bytes).err();
+        let read=|bytes:&[u8]| MerkleTree::read_from(hash_two as HashForTwo<Vec<u8>>, bytes).err().map(|error| error.kind());
+
+        let huge=1u64 << 62;
+        let zero_width=header(0, huge, (huge << 1)-1);
+        assert_eq!(decode(&zero_width), Some("Hash width must be positive"));
+        assert_eq!(read(&zero_width), Some(io::ErrorKind::InvalidData));
+        let overflowing=header(32, u64::MAX, u64::MAX);
+        assert_eq!(decode(&overflowing), Some("Stored length does not match the leaf count"));
+        assert_eq!(read(&overflowing), Some(io::ErrorKind::InvalidData));
+        let too_wide=header(u32::MAX, huge, (huge << 1)-1);
+        assert_eq!(decode(&too_wide), Some("Node bytes do not match the stored length"));
+        assert_eq!(read(&too_wide), Some(io::ErrorKind::InvalidData));
+        let unbacked=header(32, huge, (huge << 1)-1);
+        assert_eq!(decode(&unbacked), Some("Node bytes do not match the stored length"));
+        assert_eq!(read(&unbacked), Some(io::ErrorKind::InvalidData));
+        assert_eq!(decode(&header(0, 0, 0)).map(|_| ()), None);
+    }
 }
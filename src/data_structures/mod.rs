@@ -1,8 +1,20 @@
+pub mod array_stack;
 pub mod avl_tree;
 pub mod binary_search_tree;
+pub mod bounded_queue;
+pub mod deque;
 pub mod heap;
+pub mod index_list;
 pub mod linked_list;
+pub mod lock_free_queue;
+pub mod merkle_hash;
+pub mod merkle_hasher;
+pub mod merkle_mountain_range;
+pub mod merkle_root_builder;
 pub mod merkle_tree;
+pub mod priority_queue;
 pub mod set;
+pub mod sparse_merkle_tree;
 pub mod queue;
-pub mod stack;
\ No newline at end of file
+pub mod stack;
+pub mod sync_linked_list;
\ No newline at end of file
@@ -0,0 +1,313 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::FromIterator;
+use data_structures::queue::{Enqueue, Dequeue};
+
+/// Decides which of two priorities is dequeued first: the one that compares Greater
+pub trait Comparator<P> {
+    fn compare(&self, a:&P, b:&P) -> Ordering;
+}
+
+/// Dequeue the largest priority first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Max;
+
+/// Dequeue the smallest priority first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Min;
+
+impl<P> Comparator<P> for Max where P : Ord {
+    fn compare(&self, a:&P, b:&P) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<P> Comparator<P> for Min where P : Ord {
+    fn compare(&self, a:&P, b:&P) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+impl<P, F> Comparator<P> for F where F : Fn(&P, &P) -> Ordering {
+    fn compare(&self, a:&P, b:&P) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// Priority an element carries itself, used when it is enqueued through the Enqueue trait
+pub trait Prioritized<P> {
+    fn priority(&self) -> P;
+}
+
+struct Entry<T, P> {
+    data:T,
+    priority:P,
+    /// Order of insertion, so that elements of equal priority leave in the order they came
+    sequence:u64
+}
+
+/// Queue that dequeues by priority, and in insertion order among equal priorities
+///
+/// The priorities are kept apart from the elements and ordered by the comparator, which is Max,
+/// Min or any Fn(&P, &P) -> Ordering. Since it also implements Enqueue and Dequeue, code written
+/// against those traits works the same with a Queue or a PriorityQueue.
+/// # Example
+///
+/// ```
+/// use mldsa_rust::data_structures::priority_queue::PriorityQueue;
+/// let mut queue=PriorityQueue::new_min();
+/// queue.push("write", 2);
+/// queue.push("read", 1);
+/// queue.push("flush", 2);
+/// assert_eq!(queue.pop(), Some(("read", 1)));
+/// assert_eq!(queue.pop(), Some(("write", 2)));
+/// assert_eq!(queue.pop(), Some(("flush", 2)));
+/// ```
+pub struct PriorityQueue<T, P, C=Max> {
+    heap:Vec<Entry<T, P>>,
+    comparator:C,
+    next_sequence:u64
+}
+
+impl<T, P> PriorityQueue<T, P, Max> where P : Ord {
+    /// Construct a queue that dequeues the largest priority first
+    pub fn new() -> PriorityQueue<T, P, Max> {
+        PriorityQueue::with_comparator(Max)
+    }
+}
+
+impl<T, P> PriorityQueue<T, P, Min> where P : Ord {
+    /// Construct a queue that dequeues the smallest priority first
+    pub fn new_min() -> PriorityQueue<T, P, Min> {
+        PriorityQueue::with_comparator(Min)
+    }
+}
+
+impl<T, P, C> PriorityQueue<T, P, C> where C : Comparator<P> {
+    /// Construct a queue that dequeues first the priority the comparator finds Greater
+    pub fn with_comparator(comparator:C) -> PriorityQueue<T, P, C> {
+        PriorityQueue {
+            heap: Vec::new(),
+            comparator,
+            next_sequence: 0
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    pub fn push(&mut self, data:T, priority:P) {
+        let sequence=self.next_sequence;
+        self.next_sequence+=1;
+        self.heap.push(Entry { data, priority, sequence });
+        let last=self.heap.len()-1;
+        self.sift_up(last);
+    }
+
+    /// Remove the element that comes first, with its priority
+    pub fn pop(&mut self) -> Option<(T, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let entry=self.heap.swap_remove(0);
+        self.sift_down(0);
+        Some((entry.data, entry.priority))
+    }
+
+    /// Return the element that comes first, with its priority, without removing it
+    pub fn peek(&self) -> Option<(&T, &P)> {
+        self.heap.first().map(|entry| (&entry.data, &entry.priority))
+    }
+
+    /// Iterate over the elements and their priorities in no particular order
+    pub fn iter(&self) -> impl Iterator<Item=(&T, &P)> {
+        self.heap.iter().map(|entry| (&entry.data, &entry.priority))
+    }
+
+    fn precedes(&self, a:usize, b:usize) -> bool {
+        let (a, b)=(&self.heap[a], &self.heap[b]);
+        match self.comparator.compare(&a.priority, &b.priority) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => a.sequence < b.sequence
+        }
+    }
+
+    fn sift_up(&mut self, index:usize) {
+        let mut index=index;
+        while index > 0 {
+            let parent=(index-1)/2;
+            if !self.precedes(index, parent) {
+                break;
+            }
+            self.heap.swap(index, parent);
+            index=parent;
+        }
+    }
+
+    fn sift_down(&mut self, index:usize) {
+        let mut index=index;
+        loop {
+            let mut first=index;
+            for child in [2*index+1, 2*index+2].iter().cloned() {
+                if child < self.heap.len() && self.precedes(child, first) {
+                    first=child;
+                }
+            }
+            if first == index {
+                return;
+            }
+            self.heap.swap(index, first);
+            index=first;
+        }
+    }
+}
+
+impl<T, P, C> Enqueue<T> for PriorityQueue<T, P, C> where T : Clone + Prioritized<P>, C : Comparator<P> {
+    fn enqueue(&mut self, data:&T) {
+        self.push(data.clone(), data.priority());
+    }
+}
+
+impl<T, P, C> Dequeue<T> for PriorityQueue<T, P, C> where T : Clone, C : Comparator<P> {
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop().map(|(data, _)| data)
+    }
+}
+
+impl<T, P, C> Default for PriorityQueue<T, P, C> where C : Comparator<P> + Default {
+    fn default() -> PriorityQueue<T, P, C> {
+        PriorityQueue::with_comparator(C::default())
+    }
+}
+
+impl<T, P, C> FromIterator<(T, P)> for PriorityQueue<T, P, C> where C : Comparator<P> + Default {
+    fn from_iter<I: IntoIterator<Item=(T, P)>>(iter:I) -> PriorityQueue<T, P, C> {
+        let mut queue=PriorityQueue::default();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T, P, C> Extend<(T, P)> for PriorityQueue<T, P, C> where C : Comparator<P> {
+    fn extend<I: IntoIterator<Item=(T, P)>>(&mut self, iter:I) {
+        for (data, priority) in iter {
+            self.push(data, priority);
+        }
+    }
+}
+
+impl<T, P, C> fmt::Debug for PriorityQueue<T, P, C> where T : fmt::Debug, P : fmt::Debug, C : Comparator<P> {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PriorityQueue, Prioritized, Min};
+    use data_structures::queue::{Queue, Enqueue, Dequeue};
+    extern crate rand;
+    use self::rand::{Rng, SeedableRng};
+    use self::rand::rngs::StdRng;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Job {
+        name:&'static str,
+        urgency:u8
+    }
+
+    impl Prioritized<u8> for Job {
+        fn priority(&self) -> u8 {
+            self.urgency
+        }
+    }
+
+    fn job(name:&'static str, urgency:u8) -> Job {
+        Job { name, urgency }
+    }
+
+    /// Scheduler written against the queue traits only
+    fn run<Q>(queue:&mut Q, jobs:&[Job]) -> Vec<&'static str> where Q : Enqueue<Job> + Dequeue<Job> {
+        for job in jobs {
+            queue.enqueue(job);
+        }
+        let mut order=Vec::new();
+        while let Some(job)=queue.dequeue() {
+            order.push(job.name);
+        }
+        order
+    }
+
+    #[test]
+    fn it_pops_the_largest_priority_first() {
+        let mut queue=PriorityQueue::new();
+        assert_eq!(queue.peek(), None);
+        assert_eq!(queue.pop(), None);
+        for (data, priority) in [('a', 3), ('b', 9), ('c', 1), ('d', 5)] {
+            queue.push(data, priority);
+        }
+        assert_eq!(queue.peek(), Some((&'b', &9)));
+        assert_eq!(queue.size(), 4);
+        let order:Vec<char>=(0..4).map(|_| queue.pop().unwrap().0).collect();
+        assert_eq!(order, vec!['b', 'd', 'a', 'c']);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn it_orders_by_min_or_a_custom_comparator() {
+        let mut queue:PriorityQueue<u32, u32, Min>=(0..20).map(|i| (i, (i*7) % 20)).collect();
+        let order:Vec<u32>=(0..20).map(|_| queue.pop().unwrap().1).collect();
+        assert_eq!(order, (0..20).collect::<Vec<u32>>());
+
+        let mut queue=PriorityQueue::with_comparator(|a:&String, b:&String| b.len().cmp(&a.len()));
+        queue.extend(vec![(1, "ccc".to_string()), (2, "a".to_string()), (3, "bb".to_string())]);
+        assert_eq!(queue.pop(), Some((2, "a".to_string())));
+        assert_eq!(queue.pop(), Some((3, "bb".to_string())));
+    }
+
+    #[test]
+    fn it_keeps_insertion_order_among_equal_priorities() {
+        let mut rng=StdRng::from_seed([25u8; 32]);
+        let mut queue=PriorityQueue::new_min();
+        let mut expected:Vec<(u8, usize)>=Vec::new();
+        for i in 0..500 {
+            let priority=rng.gen::<u8>() % 8;
+            queue.push(i, priority);
+            expected.push((priority, i));
+        }
+        expected.sort();
+        let order:Vec<(u8, usize)>=(0..500).map(|_| queue.pop().map(|(data, priority)| (priority, data)).unwrap()).collect();
+        assert_eq!(order, expected);
+    }
+
+    #[test]
+    fn it_replaces_a_queue_in_code_using_the_traits() {
+        let jobs=vec![job("log", 1), job("alert", 9), job("index", 4), job("page", 9)];
+        assert_eq!(run(&mut Queue::new(), &jobs), vec!["log", "alert", "index", "page"]);
+        assert_eq!(run(&mut PriorityQueue::new(), &jobs), vec!["alert", "page", "index", "log"]);
+        assert_eq!(run(&mut PriorityQueue::new_min(), &jobs), vec!["log", "index", "alert", "page"]);
+    }
+
+    #[test]
+    fn it_formats_and_clears() {
+        let mut queue:PriorityQueue<char, u8>=vec![('x', 1)].into_iter().collect();
+        assert_eq!(format!("{:?}", queue), "[('x', 1)]");
+        queue.clear();
+        assert!(queue.empty());
+        assert_eq!(queue.dequeue(), None);
+    }
+}
@@ -1,5 +1,7 @@
-use data_structures::linked_list::LinkedList;
+use std::iter::FromIterator;
+use data_structures::linked_list::{LinkedList, Iter, IntoIter};
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Queue<T> where T : Clone {
     list:LinkedList<T>
 }
@@ -14,7 +16,7 @@ pub trait Dequeue<T> where T : Clone {
 
 impl<T> Enqueue<T> for Queue<T> where T : Clone {
     fn enqueue(&mut self, data:&T) {
-        self.list.insert_back(data);
+        self.list.insert_back(data.clone());
     }
 }
 
@@ -39,6 +41,45 @@ impl<T> Queue<T> where T : Clone {
     pub fn size(&self) -> usize {
         self.list.size()
     }
+
+    /// Iterate over the elements front to back
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+}
+
+impl<T> Default for Queue<T> where T : Clone {
+    fn default() -> Queue<T> {
+        Queue::new()
+    }
+}
+
+impl<T> FromIterator<T> for Queue<T> where T : Clone {
+    fn from_iter<I: IntoIterator<Item=T>>(iter:I) -> Queue<T> {
+        Queue { list: iter.into_iter().collect() }
+    }
+}
+
+impl<T> Extend<T> for Queue<T> where T : Clone {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter:I) {
+        self.list.extend(iter);
+    }
+}
+
+impl<T> IntoIterator for Queue<T> where T : Clone {
+    type Item=T;
+    type IntoIter=IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        self.list.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Queue<T> where T : Clone {
+    type Item=&'a T;
+    type IntoIter=Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
 }
 
 #[cfg(test)]
@@ -84,4 +125,23 @@ mod tests{
         }
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn queue_collects_and_dequeues_in_order() {
+        let mut queue:Queue<u32>=(0..3).collect();
+        queue.extend(vec![3, 4]);
+        assert_eq!(queue.iter().cloned().collect::<Vec<u32>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(queue.dequeue(), Some(0));
+        assert_eq!(queue.into_iter().collect::<Vec<u32>>(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn queue_clones_compares_and_formats() {
+        let queue:Queue<u32>=(0..3).collect();
+        let mut copy=queue.clone();
+        assert_eq!(queue, copy);
+        assert_eq!(format!("{:?}", queue), "Queue { list: [0, 1, 2] }");
+        copy.dequeue();
+        assert_ne!(queue, copy);
+        assert_eq!(Queue::<u32>::default(), Queue::new());
+    }
+}
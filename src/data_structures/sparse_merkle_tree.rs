@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+pub use data_structures::merkle_hasher::{MerkleHasher, Blake2bHasher};
+
+/// Number of bits in a key, which is also the height of the tree
+pub const KEY_BITS: usize = 256;
+
+/// A 256-bit path from the root to a leaf, most significant bit first
+pub type SparseKey = [u8; 32];
+
+/// Merkle tree over every possible 256-bit key in which only the non-empty subtrees are stored
+///
+/// An absent key holds the empty leaf, so the root commits to the absence of a key as well as to
+/// the value hashes that are present. Nodes are stored by their height above the leaves and the
+/// key prefix leading to them.
+pub struct SparseMerkleTree<HashValueType, Hasher> {
+    hasher: Hasher,
+    /// defaults[height] is the hash of an empty subtree of that height
+    defaults: Vec<HashValueType>,
+    leaves: HashMap<SparseKey, HashValueType>,
+    nodes: HashMap<(usize, SparseKey), HashValueType>
+}
+
+/// Proof of the value, or of the absence, of a key in a sparse merkle tree
+///
+/// Siblings that are empty subtrees are left out and marked with a cleared bit in the bitmap,
+/// so a proof in a tree holding n keys carries about log2(n) hashes instead of 256.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseMerkleProof<HashValueType> {
+    bitmap: [u8; 32],
+    siblings: Vec<HashValueType>
+}
+
+/// Return the bit of the key at the given depth below the root
+fn bit_at(key:&SparseKey, depth:usize) -> bool {
+    key[depth/8] & (0x80 >> (depth%8)) != 0
+}
+
+/// Return the key with the bits below the node of the given height cleared
+fn prefix_of(key:&SparseKey, height:usize) -> SparseKey {
+    let mut prefix=*key;
+    for depth in KEY_BITS-height..KEY_BITS {
+        prefix[depth/8] &= !(0x80 >> (depth%8));
+    }
+    prefix
+}
+
+/// Return the hashes of empty subtrees from the empty leaf up to the empty root, which proofs are verified with
+pub fn empty_subtree_hashes<HashValueType, Hasher>(hasher:&Hasher, empty_leaf:HashValueType) -> Vec<HashValueType>
+    where Hasher : MerkleHasher<HashValueType> {
+    let mut defaults=Vec::with_capacity(KEY_BITS+1);
+    defaults.push(empty_leaf);
+    for height in 0..KEY_BITS {
+        let parent=hasher.hash_node(&defaults[height], &defaults[height]);
+        defaults.push(parent);
+    }
+    defaults
+}
+
+impl SparseMerkleTree<Vec<u8>, Blake2bHasher> {
+    /// Construct an empty tree hashed with domain separated Blake2b-256 and an all zero empty leaf
+    pub fn blake2b_256() -> SparseMerkleTree<Vec<u8>, Blake2bHasher> {
+        SparseMerkleTree::new(Blake2bHasher::blake2b_256().domain_separated(), vec![0u8; 32])
+    }
+}
+
+impl<HashValueType, Hasher> SparseMerkleTree<HashValueType, Hasher> where HashValueType : Clone + PartialEq, Hasher : MerkleHasher<HashValueType> {
+    /// Construct an empty tree in which every absent key holds empty_leaf
+    pub fn new(hasher:Hasher, empty_leaf:HashValueType) -> SparseMerkleTree<HashValueType, Hasher> {
+        let defaults=empty_subtree_hashes(&hasher, empty_leaf);
+        SparseMerkleTree {
+            hasher,
+            defaults,
+            leaves: HashMap::new(),
+            nodes: HashMap::new()
+        }
+    }
+
+    /// Return the number of keys present in the tree
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Return the value hash stored for the key
+    pub fn get(&self, key:&SparseKey) -> Option<&HashValueType> {
+        self.leaves.get(key)
+    }
+
+    /// Return the hashing scheme of this tree
+    pub fn hasher(&self) -> &Hasher {
+        &self.hasher
+    }
+
+    /// Return the hashes of empty subtrees of this tree, indexed by height, to verify its proofs with
+    pub fn defaults(&self) -> &[HashValueType] {
+        &self.defaults
+    }
+
+    /// Return the hash value of the root
+    pub fn root(&self) -> HashValueType {
+        self.node(KEY_BITS, &[0u8; 32])
+    }
+
+    /// Store the value hash for the key, rehash the path above it and return the new root value
+    ///
+    /// Fails when the value hash makes the empty leaf, as a value equal to the empty leaf does with a
+    /// hasher that stores leaves unhashed, since the key would then look absent.
+    pub fn insert(&mut self, key:&SparseKey, value_hash:HashValueType) -> Result<HashValueType, &'static str> {
+        let leaf=self.hasher.hash_leaf(&value_hash);
+        if leaf == self.defaults[0] {
+            return Err("Value hash makes the empty leaf");
+        }
+        self.leaves.insert(*key, value_hash);
+        self.set_node(0, *key, leaf);
+        Ok(self.rehash_path(key))
+    }
+
+    /// Remove the key, rehash the path above it and return the value hash it held
+    pub fn remove(&mut self, key:&SparseKey) -> Option<HashValueType> {
+        let removed=self.leaves.remove(key);
+        if removed.is_some() {
+            let empty_leaf=self.defaults[0].clone();
+            self.set_node(0, *key, empty_leaf);
+            self.rehash_path(key);
+        }
+        removed
+    }
+
+    /// Build the proof of the value of the key, or of its absence when the key is not present
+    /// # Example
+    ///
+    /// ```
+    /// use mldsa_rust::data_structures::sparse_merkle_tree::SparseMerkleTree;
+    /// let mut tree=SparseMerkleTree::blake2b_256();
+    /// tree.insert(&[1u8; 32], vec![7u8; 32]).unwrap();
+    /// let proof=tree.proof(&[1u8; 32]);
+    /// assert!(proof.verify(&tree.root(), &[1u8; 32], Some(&vec![7u8; 32]), tree.hasher(), tree.defaults()));
+    /// let proof=tree.proof(&[2u8; 32]);
+    /// assert!(proof.verify(&tree.root(), &[2u8; 32], None, tree.hasher(), tree.defaults()));
+    /// ```
+    pub fn proof(&self, key:&SparseKey) -> SparseMerkleProof<HashValueType> {
+        let mut bitmap=[0u8; 32];
+        let mut siblings=Vec::new();
+        for height in 0..KEY_BITS {
+            let sibling=self.sibling_prefix(key, height);
+            if let Some(node)=self.nodes.get(&(height, sibling)) {
+                bitmap[height/8] |= 0x80 >> (height%8);
+                siblings.push(node.clone());
+            }
+        }
+        SparseMerkleProof { bitmap, siblings }
+    }
+
+    fn node(&self, height:usize, prefix:&SparseKey) -> HashValueType {
+        self.nodes.get(&(height, *prefix)).cloned().unwrap_or_else(|| self.defaults[height].clone())
+    }
+
+    /// Store a node, or drop it when it is the hash of an empty subtree so only non-empty subtrees are kept
+    fn set_node(&mut self, height:usize, prefix:SparseKey, node:HashValueType) {
+        if node == self.defaults[height] {
+            self.nodes.remove(&(height, prefix));
+        } else {
+            self.nodes.insert((height, prefix), node);
+        }
+    }
+
+    fn sibling_prefix(&self, key:&SparseKey, height:usize) -> SparseKey {
+        let mut sibling=prefix_of(key, height);
+        let depth=KEY_BITS-1-height;
+        sibling[depth/8] ^= 0x80 >> (depth%8);
+        sibling
+    }
+
+    fn rehash_path(&mut self, key:&SparseKey) -> HashValueType {
+        let mut current=self.node(0, key);
+        for height in 0..KEY_BITS {
+            let sibling=self.node(height, &self.sibling_prefix(key, height));
+            current = if bit_at(key, KEY_BITS-1-height) {
+                self.hasher.hash_node(&sibling, &current)
+            } else {
+                self.hasher.hash_node(&current, &sibling)
+            };
+            self.set_node(height+1, prefix_of(key, height+1), current.clone());
+        }
+        current
+    }
+}
+
+impl<HashValueType> SparseMerkleProof<HashValueType> where HashValueType : Clone + PartialEq {
+    /// Return the non-empty siblings ordered from the leaf level up to the root
+    pub fn siblings(&self) -> &Vec<HashValueType> {
+        &self.siblings
+    }
+
+    /// Return whether the sibling at the given height above the leaves is carried in the proof
+    pub fn has_sibling(&self, height:usize) -> bool {
+        self.bitmap[height/8] & (0x80 >> (height%8)) != 0
+    }
+
+    /// Check that the key holds value_hash under the root, or that it is absent when value_hash is None
+    ///
+    /// defaults are the hashes of empty subtrees of the tree, as returned by its defaults or by
+    /// empty_subtree_hashes, since the omitted siblings are taken from them.
+    pub fn verify<Hasher>(&self, root:&HashValueType, key:&SparseKey, value_hash:Option<&HashValueType>, hasher:&Hasher,
+                          defaults:&[HashValueType]) -> bool where Hasher : MerkleHasher<HashValueType> {
+        if defaults.len() != KEY_BITS+1 {
+            return false;
+        }
+        let mut current=match value_hash {
+            Some(value_hash) => hasher.hash_leaf(value_hash),
+            None => defaults[0].clone()
+        };
+        let mut siblings=self.siblings.iter();
+        for (height, default) in defaults[..KEY_BITS].iter().enumerate() {
+            let sibling = if self.has_sibling(height) {
+                match siblings.next() {
+                    Some(sibling) => sibling,
+                    None => return false
+                }
+            } else {
+                default
+            };
+            current = if bit_at(key, KEY_BITS-1-height) {
+                hasher.hash_node(sibling, &current)
+            } else {
+                hasher.hash_node(&current, sibling)
+            };
+        }
+        siblings.next().is_none() && current == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blake2_rfc::blake2b::blake2b;
+
+    fn key(seed:u8) -> SparseKey {
+        let mut key=[0u8; 32];
+        key.copy_from_slice(blake2b(32, &[], &[seed]).as_bytes());
+        key
+    }
+
+    fn value(seed:u8) -> Vec<u8> {
+        vec![seed; 32]
+    }
+
+    #[test]
+    fn it_has_the_empty_root_when_empty() {
+        let tree=SparseMerkleTree::blake2b_256();
+        let defaults=empty_subtree_hashes(tree.hasher(), vec![0u8; 32]);
+        assert_eq!(tree.root(), defaults[KEY_BITS]);
+        assert_eq!(tree.defaults(), &defaults[..]);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn it_returns_to_the_empty_root_after_removing_every_key() {
+        let mut tree=SparseMerkleTree::blake2b_256();
+        let empty_root=tree.root();
+        for seed in 0..10 {
+            tree.insert(&key(seed), value(seed)).unwrap();
+        }
+        assert_eq!(tree.len(), 10);
+        assert_ne!(tree.root(), empty_root);
+        for seed in 0..10 {
+            assert_eq!(tree.remove(&key(seed)), Some(value(seed)));
+        }
+        assert_eq!(tree.root(), empty_root);
+        assert!(tree.nodes.is_empty());
+        assert_eq!(tree.remove(&key(0)), None);
+    }
+
+    #[test]
+    fn it_makes_the_same_root_in_any_insertion_order() {
+        let mut forward=SparseMerkleTree::blake2b_256();
+        let mut backward=SparseMerkleTree::blake2b_256();
+        for seed in 0..10 {
+            forward.insert(&key(seed), value(seed)).unwrap();
+            backward.insert(&key(9-seed), value(9-seed)).unwrap();
+        }
+        assert_eq!(forward.root(), backward.root());
+        forward.insert(&key(3), value(42)).unwrap();
+        assert_ne!(forward.root(), backward.root());
+        assert_eq!(forward.get(&key(3)), Some(&value(42)));
+    }
+
+    #[test]
+    fn it_verifies_membership_proofs() {
+        let mut tree=SparseMerkleTree::blake2b_256();
+        for seed in 0..10 {
+            tree.insert(&key(seed), value(seed)).unwrap();
+        }
+        let root=tree.root();
+        for seed in 0..10 {
+            let proof=tree.proof(&key(seed));
+            assert!(proof.verify(&root, &key(seed), Some(&value(seed)), tree.hasher(), tree.defaults()));
+            assert!(!proof.verify(&root, &key(seed), Some(&value(seed+1)), tree.hasher(), tree.defaults()));
+            assert!(!proof.verify(&root, &key(seed), None, tree.hasher(), tree.defaults()));
+        }
+    }
+
+    #[test]
+    fn it_verifies_non_membership_proofs() {
+        let mut tree=SparseMerkleTree::blake2b_256();
+        for seed in 0..10 {
+            tree.insert(&key(seed), value(seed)).unwrap();
+        }
+        let root=tree.root();
+        for seed in 10..20 {
+            let proof=tree.proof(&key(seed));
+            assert!(proof.verify(&root, &key(seed), None, tree.hasher(), tree.defaults()));
+            assert!(!proof.verify(&root, &key(seed), Some(&value(seed)), tree.hasher(), tree.defaults()));
+        }
+        let defaults=empty_subtree_hashes(tree.hasher(), vec![0u8; 32]);
+        assert!(tree.proof(&key(10)).verify(&root, &key(10), None, tree.hasher(), &defaults));
+        assert!(!tree.proof(&key(10)).verify(&root, &key(10), None, tree.hasher(), &defaults[..KEY_BITS]));
+    }
+
+    #[test]
+    fn it_compresses_proofs_by_leaving_out_empty_siblings() {
+        let mut tree=SparseMerkleTree::blake2b_256();
+        let proof=tree.proof(&key(0));
+        assert!(proof.siblings().is_empty());
+        for seed in 0..16 {
+            tree.insert(&key(seed), value(seed)).unwrap();
+        }
+        let proof=tree.proof(&key(0));
+        assert!(proof.siblings().len() < 16);
+        assert_eq!(proof.siblings().len(), (0..KEY_BITS).filter(|height| proof.has_sibling(*height)).count());
+    }
+
+    #[test]
+    fn it_hashes_sibling_keys_into_a_shared_parent() {
+        let hasher=Blake2bHasher::blake2b_256().domain_separated();
+        let mut tree=SparseMerkleTree::new(hasher.clone(), vec![0u8; 32]);
+        let left=[0u8; 32];
+        let mut right=[0u8; 32];
+        right[31]=1;
+        tree.insert(&left, value(1)).unwrap();
+        tree.insert(&right, value(2)).unwrap();
+        let parent=hasher.hash_node(&hasher.hash_leaf(&value(1)), &hasher.hash_leaf(&value(2)));
+        assert_eq!(tree.nodes.get(&(1, left)), Some(&parent));
+    }
+
+    #[test]
+    fn it_rejects_a_value_that_makes_the_empty_leaf() {
+        let mut tree=SparseMerkleTree::new(Blake2bHasher::blake2b_256(), vec![0u8; 32]);
+        assert!(tree.insert(&key(0), vec![0u8; 32]).is_err());
+        assert!(tree.is_empty());
+        assert!(tree.proof(&key(0)).verify(&tree.root(), &key(0), None, tree.hasher(), tree.defaults()));
+        assert!(tree.insert(&key(0), value(1)).is_ok());
+    }
+}
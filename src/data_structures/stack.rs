@@ -1,5 +1,7 @@
-use data_structures::linked_list::LinkedList;
+use std::iter::FromIterator;
+use data_structures::linked_list::{LinkedList, Iter, IntoIter};
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Stack<T> where T : Clone {
     list: LinkedList<T>
 }
@@ -26,11 +28,50 @@ impl<T> Stack<T> where T : Clone {
     pub fn is_empty(&self) -> bool {
         self.list.is_empty()
     }
+
+    /// Iterate over the elements bottom to top
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+}
+
+impl<T> Default for Stack<T> where T : Clone {
+    fn default() -> Stack<T> {
+        Stack::new()
+    }
+}
+
+impl<T> FromIterator<T> for Stack<T> where T : Clone {
+    fn from_iter<I: IntoIterator<Item=T>>(iter:I) -> Stack<T> {
+        Stack { list: iter.into_iter().collect() }
+    }
+}
+
+impl<T> Extend<T> for Stack<T> where T : Clone {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter:I) {
+        self.list.extend(iter);
+    }
+}
+
+impl<T> IntoIterator for Stack<T> where T : Clone {
+    type Item=T;
+    type IntoIter=IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        self.list.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Stack<T> where T : Clone {
+    type Item=&'a T;
+    type IntoIter=Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
 }
 
 impl<T> Push<T> for Stack<T> where T : Clone {
     fn push(&mut self, data:&T) {
-        self.list.insert_back(data);
+        self.list.insert_back(data.clone());
     }
 }
 
@@ -80,4 +121,23 @@ mod tests {
         }
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_collect_and_pop_in_reverse() {
+        let mut stack:Stack<u32>=(0..3).collect();
+        stack.extend(vec![3, 4]);
+        assert_eq!((&stack).into_iter().cloned().collect::<Vec<u32>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.into_iter().rev().collect::<Vec<u32>>(), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_clone_compare_and_format() {
+        let stack:Stack<u32>=(0..3).collect();
+        let mut copy=stack.clone();
+        assert_eq!(stack, copy);
+        assert_eq!(format!("{:?}", stack), "Stack { list: [0, 1, 2] }");
+        copy.push(&3);
+        assert_ne!(stack, copy);
+        assert_eq!(Stack::<u32>::default(), Stack::new());
+    }
+}
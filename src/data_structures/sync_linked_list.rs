@@ -0,0 +1,412 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+/// Every node is a box owned by the list, turned into a raw pointer so that both neighbours can point at it
+struct Node<T>{
+    prev:Link<T>,
+    next:Link<T>,
+    data:T
+}
+
+/// Doubly linked list with the methods of `LinkedList` that can be sent to and shared between threads
+///
+/// It owns its boxed nodes outright instead of sharing them through `Rc<RefCell<..>>`,
+/// so it is Send when the elements are Send and Sync when they are Sync.
+pub struct SyncLinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    size: usize,
+    marker: PhantomData<Box<Node<T>>>
+}
+
+// The list is the only owner of its nodes and only hands out references tied to its own borrows
+unsafe impl<T> Send for SyncLinkedList<T> where T : Send {}
+unsafe impl<T> Sync for SyncLinkedList<T> where T : Sync {}
+
+/// Iterator over references to the elements, from either end
+pub struct Iter<'a, T: 'a> {
+    next_node:Link<T>,
+    back_node:Link<T>,
+    remaining:usize,
+    marker:PhantomData<&'a Node<T>>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item=&'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.next_node.map(|node| {
+            let node=unsafe { &*node.as_ptr() };
+            self.next_node=node.next;
+            self.remaining-=1;
+            &node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back_node.map(|node| {
+            let node=unsafe { &*node.as_ptr() };
+            self.back_node=node.prev;
+            self.remaining-=1;
+            &node.data
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// Iterator over mutable references to the elements, from either end
+pub struct IterMut<'a, T: 'a> {
+    next_node:Link<T>,
+    back_node:Link<T>,
+    remaining:usize,
+    marker:PhantomData<&'a mut Node<T>>
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item=&'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.next_node.map(|node| {
+            // The count stops both ends before they meet, so every node is handed out once
+            let node=unsafe { &mut *node.as_ptr() };
+            self.next_node=node.next;
+            self.remaining-=1;
+            &mut node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back_node.map(|node| {
+            let node=unsafe { &mut *node.as_ptr() };
+            self.back_node=node.prev;
+            self.remaining-=1;
+            &mut node.data
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+/// Iterator that moves the elements out of the list, from either end
+pub struct IntoIter<T> {
+    list:SyncLinkedList<T>
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item=T;
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> SyncLinkedList<T> {
+    pub fn new() -> SyncLinkedList<T> {
+        SyncLinkedList {
+            head: None,
+            tail: None,
+            size: 0,
+            marker: PhantomData
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Iterate over references to the elements, from the front or with rev() from the back
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next_node: self.head, back_node: self.tail, remaining: self.size, marker: PhantomData }
+    }
+
+    /// Iterate over mutable references to the elements
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { next_node: self.head, back_node: self.tail, remaining: self.size, marker: PhantomData }
+    }
+
+    pub fn count_front(&self) -> usize {
+        let mut node=self.head;
+        let mut count=0;
+        while let Some(link)=node {
+            count+=1;
+            node=unsafe { (*link.as_ptr()).next };
+        }
+        count
+    }
+
+    pub fn count_back(&self) -> usize {
+        let mut node=self.tail;
+        let mut count=0;
+        while let Some(link)=node {
+            count+=1;
+            node=unsafe { (*link.as_ptr()).prev };
+        }
+        count
+    }
+
+    pub fn insert_back(&mut self, data:T) {
+        let node=NonNull::from(Box::leak(Box::new(Node { prev: self.tail, next: None, data })));
+        match self.tail {
+            Some(tail) => unsafe { (*tail.as_ptr()).next=Some(node) },
+            None => self.head=Some(node)
+        }
+        self.tail=Some(node);
+        self.size+=1;
+    }
+
+    pub fn insert_front(&mut self, data:T) {
+        let node=NonNull::from(Box::leak(Box::new(Node { prev: None, next: self.head, data })));
+        match self.head {
+            Some(head) => unsafe { (*head.as_ptr()).prev=Some(node) },
+            None => self.tail=Some(node)
+        }
+        self.head=Some(node);
+        self.size+=1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|head| {
+            // The node was leaked from a box by an insert and is unlinked here, so it is freed exactly once
+            let node=unsafe { Box::from_raw(head.as_ptr()) };
+            self.head=node.next;
+            match self.head {
+                Some(next) => unsafe { (*next.as_ptr()).prev=None },
+                None => self.tail=None
+            }
+            self.size-=1;
+            node.data
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|tail| {
+            let node=unsafe { Box::from_raw(tail.as_ptr()) };
+            self.tail=node.prev;
+            match self.tail {
+                Some(prev) => unsafe { (*prev.as_ptr()).next=None },
+                None => self.head=None
+            }
+            self.size-=1;
+            node.data
+        })
+    }
+}
+
+impl<T> SyncLinkedList<T> where T: Clone {
+    pub fn front(&self) -> Result<T, &str> {
+        self.iter().next().cloned().ok_or("No element")
+    }
+
+    pub fn back(&self) -> Result<T, &str> {
+        self.iter().next_back().cloned().ok_or("No element")
+    }
+}
+
+/// Frees the nodes one at a time, as each pop unlinks the front node before its box is dropped
+impl<T> Drop for SyncLinkedList<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<T> IntoIterator for SyncLinkedList<T> {
+    type Item=T;
+    type IntoIter=IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SyncLinkedList<T> {
+    type Item=&'a T;
+    type IntoIter=Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SyncLinkedList<T> {
+    type Item=&'a mut T;
+    type IntoIter=IterMut<'a, T>;
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for SyncLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter:I) -> SyncLinkedList<T> {
+        let mut list=SyncLinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for SyncLinkedList<T> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter:I) {
+        for data in iter {
+            self.insert_back(data);
+        }
+    }
+}
+
+impl<T> Default for SyncLinkedList<T> {
+    fn default() -> SyncLinkedList<T> {
+        SyncLinkedList::new()
+    }
+}
+
+impl<T> Clone for SyncLinkedList<T> where T : Clone {
+    fn clone(&self) -> SyncLinkedList<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> fmt::Debug for SyncLinkedList<T> where T : fmt::Debug {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for SyncLinkedList<T> where T : PartialEq {
+    fn eq(&self, other:&SyncLinkedList<T>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for SyncLinkedList<T> where T : Eq {}
+
+#[cfg(test)]
+mod tests {
+    use super::SyncLinkedList;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn assert_list(list:&SyncLinkedList<i32>, expected:&[i32]) {
+        assert_eq!(list.iter().cloned().collect::<Vec<i32>>(), expected.to_vec());
+        assert_eq!(list.iter().rev().count(), expected.len());
+        assert_eq!(list.size(), expected.len());
+        assert_eq!(list.count_front(), expected.len());
+        assert_eq!(list.count_back(), expected.len());
+    }
+
+    #[test]
+    fn it_inserts_and_pops_at_both_ends() {
+        let mut list=SyncLinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.front(), Err("No element"));
+        list.insert_back(2);
+        list.insert_front(1);
+        list.insert_back(3);
+        assert_list(&list, &[1, 2, 3]);
+        assert_eq!((list.front(), list.back()), (Ok(1), Ok(3)));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_list(&list, &[2]);
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.pop_front(), None);
+        assert_list(&list, &[]);
+    }
+
+    #[test]
+    fn it_iterates_from_both_ends() {
+        let mut list:SyncLinkedList<i32>=(1..5).collect();
+        for val in &mut list {
+            *val*=10;
+        }
+        let mut iter=list.iter();
+        assert_eq!(iter.next_back(), Some(&40));
+        assert_eq!(iter.next(), Some(&10));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(list.clone().into_iter().rev().collect::<Vec<i32>>(), vec![40, 30, 20, 10]);
+        assert_eq!(format!("{:?}", list), "[10, 20, 30, 40]");
+    }
+
+    #[test]
+    fn it_moves_to_another_thread() {
+        let list:SyncLinkedList<String>=(0..100).map(|i| i.to_string()).collect();
+        let handle=thread::spawn(move || {
+            let mut list=list;
+            list.insert_back(String::from("done"));
+            list
+        });
+        let list=handle.join().unwrap();
+        assert_eq!(list.size(), 101);
+        assert_eq!(list.back().unwrap(), "done");
+    }
+
+    #[test]
+    fn it_is_shared_between_threads() {
+        let list:Arc<SyncLinkedList<u64>>=Arc::new((1..1001).collect());
+        let handles:Vec<_>=(0..4).map(|_| {
+            let list=list.clone();
+            thread::spawn(move || list.iter().sum::<u64>())
+        }).collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 500500);
+        }
+    }
+
+    #[test]
+    fn it_holds_payloads_that_cannot_be_cloned() {
+        let mut list=SyncLinkedList::new();
+        list.insert_back(Box::new(1));
+        list.insert_front(Box::new(0));
+        assert_eq!(*list.pop_back().unwrap(), 1);
+        assert_eq!(*list.pop_back().unwrap(), 0);
+    }
+
+    #[test]
+    fn it_drops_a_million_element_list() {
+        let shared=Arc::new(0);
+        let list:SyncLinkedList<Arc<i32>>=(0..1000000).map(|_| shared.clone()).collect();
+        drop(list);
+        assert_eq!(Arc::strong_count(&shared), 1);
+    }
+}
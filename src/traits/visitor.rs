@@ -28,7 +28,7 @@ mod tests {
     impl<T> VisitorAcceptor<T, String> for LinkedList<T> where T : Debug + Clone {
         fn accept<V>(&mut self, visitor:&mut V) -> Result<(), String> where V : Visitor<T, String>{
             for item in self.iter(){
-                visitor.visit(&item)?;
+                visitor.visit(item)?;
             }
             Ok(())
         }
@@ -38,7 +38,7 @@ mod tests {
     fn utilise_visitor_in_linked_list() {
         let mut list=LinkedList::new();
         for i in 0..10 {
-            list.insert_back(&i);
+            list.insert_back(i);
         }
 
         let mut visitor=AppendVisitor{history:Vec::new()};